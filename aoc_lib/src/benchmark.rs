@@ -4,11 +4,12 @@ use std::{
     time::{Duration, Instant},
 };
 
-use crate::tooling::{load_input, Solution};
+use crate::tooling::{load_input, Alternative, Solution};
 
 pub fn benchmarks(
-    inputs: &[[&'static str; 2]],
-    solutions: &[[Solution; 2]],
+    inputs: &[Option<[&'static str; 2]>],
+    solutions: &[Option<[Solution; 2]>],
+    alternatives: &[Alternative],
     day_option: Option<usize>,
     passes: u32,
 ) {
@@ -17,8 +18,11 @@ pub fn benchmarks(
     }
 
     let mut elapsed_total: Duration = Default::default();
-    for (i, [f1, f2]) in solutions.iter().enumerate() {
+    for (i, solution) in solutions.iter().enumerate() {
         let current_day = i + 1;
+        let Some([f1, f2]) = solution else {
+            continue;
+        };
         if let Some(day) = day_option {
             if current_day != day {
                 continue;
@@ -27,29 +31,50 @@ pub fn benchmarks(
 
         let data = || load_input(inputs, current_day, false);
 
-        let now = Instant::now();
-        for _ in 0..passes {
-            black_box(f1(data()));
-        }
-        let elapsed1 = now.elapsed();
-        let now = Instant::now();
-        for _ in 0..passes {
-            black_box(f2(data()));
-        }
-        let elapsed2 = now.elapsed();
+        let elapsed1 = time(passes, || f1(data()));
+        let elapsed2 = time(passes, || f2(data()));
         println!(
             "\n{}: {}\n{}: {}",
             format!("day{current_day:02}/task1").bold(),
-            format!("{:>10?}", elapsed1 / passes).green(),
+            format!("{elapsed1:>10?}").green(),
             format!("day{current_day:02}/task2").bold(),
-            format!("{:>10?}", elapsed2 / passes).green(),
+            format!("{elapsed2:>10?}").green(),
         );
 
         elapsed_total += elapsed1 + elapsed2;
+
+        for alt in alternatives.iter().filter(|a| a.day == current_day) {
+            let elapsed = time(passes, || (alt.solution)(data()));
+            println!(
+                "{}: {}",
+                format!("day{current_day:02}/task{} ({})", alt.task, alt.name)
+                    .bold(),
+                format!("{elapsed:>10?}").green(),
+            );
+
+            let expected = [f1, f2][alt.task - 1](data()).to_string();
+            let result = (alt.solution)(data()).to_string();
+            if result != expected {
+                eprintln!(
+                    "{}: {} gives {result} instead of {expected}",
+                    "WARNING".yellow().bold(),
+                    alt.name
+                );
+            }
+        }
     }
     println!(
         "\n{}: {}",
         "Total".bold(),
-        format!("{:>10?}", elapsed_total / passes).green()
+        format!("{elapsed_total:>10?}").green()
     );
 }
+
+/// Average time `f` takes over `passes` runs
+pub fn time<R>(passes: u32, mut f: impl FnMut() -> R) -> Duration {
+    let now = Instant::now();
+    for _ in 0..passes {
+        black_box(f());
+    }
+    now.elapsed() / passes
+}
@@ -0,0 +1,288 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Mul, Rem, Sub},
+    str::FromStr,
+};
+use thiserror::Error;
+
+type Limb = u32;
+const LIMB_BITS: usize = Limb::BITS as usize;
+/// Largest power of 10 that fits in a limb, to convert from and to decimal
+/// 9 digits at a time
+const DECIMAL_BASE: u64 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("'{0}' is not a valid unsigned integer")]
+pub struct ParseBigUintError(String);
+
+/// Unsigned integer of arbitrary size, for when the puzzles don't allow any
+/// tricks to keep the numbers small.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Little endian, without trailing zeros (so 0 has no limbs)
+    limbs: Vec<Limb>,
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint { BigUint::default() }
+
+    pub fn is_zero(&self) -> bool { self.limbs.is_empty() }
+
+    /// Amount of significant bits, 0 for 0
+    pub fn bits(&self) -> usize {
+        self.limbs.last().map_or(0, |&top| {
+            self.limbs.len() * LIMB_BITS - top.leading_zeros() as usize
+        })
+    }
+
+    /// The value, if it fits in a `u64`
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [lo] => Some(lo.into()),
+            [lo, hi] => Some(u64::from(hi) << LIMB_BITS | u64::from(lo)),
+            _ => None,
+        }
+    }
+
+    /// Quotient and remainder of the division by a small number. Panics if
+    /// `divisor` is 0.
+    pub fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+        assert!(divisor != 0, "Division of a BigUint by zero");
+        let divisor = u64::from(divisor);
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut rem = 0;
+        for (q, &limb) in quotient.iter_mut().zip(&self.limbs).rev() {
+            let current = rem << LIMB_BITS | u64::from(limb);
+            *q = (current / divisor) as Limb;
+            rem = current % divisor;
+        }
+        (BigUint::from_limbs(quotient), rem as u32)
+    }
+
+    /// Quotient and remainder, panics if `divisor` is 0.
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        if let [small] = divisor.limbs[..] {
+            let (q, r) = self.div_rem_small(small);
+            return (q, BigUint::from(u64::from(r)));
+        }
+        assert!(!divisor.is_zero(), "Division of a BigUint by zero");
+        if self < divisor {
+            return (BigUint::zero(), self.clone());
+        }
+
+        // Schoolbook binary long division, one bit of the quotient at a time
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut rem = BigUint::zero();
+        for bit in (0..self.bits()).rev() {
+            rem.shift_left_one();
+            if self.bit(bit) {
+                rem.set_lowest_bit();
+            }
+            if rem >= *divisor {
+                rem = &rem - divisor;
+                quotient[bit / LIMB_BITS] |= 1 << (bit % LIMB_BITS);
+            }
+        }
+        (BigUint::from_limbs(quotient), rem)
+    }
+
+    fn from_limbs(mut limbs: Vec<Limb>) -> BigUint {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        (self.limbs[i / LIMB_BITS] >> (i % LIMB_BITS)) & 1 != 0
+    }
+
+    fn shift_left_one(&mut self) {
+        let mut carry = 0;
+        for limb in &mut self.limbs {
+            let next_carry = *limb >> (LIMB_BITS - 1);
+            *limb = *limb << 1 | carry;
+            carry = next_carry;
+        }
+        if carry != 0 {
+            self.limbs.push(carry);
+        }
+    }
+
+    fn set_lowest_bit(&mut self) {
+        match self.limbs.first_mut() {
+            Some(limb) => *limb |= 1,
+            None => self.limbs.push(1),
+        }
+    }
+
+    /// `self * factor + addend`, used for parsing
+    fn mul_add_small(&self, factor: u32, addend: u32) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = u64::from(addend);
+        for &limb in &self.limbs {
+            let current = u64::from(limb) * u64::from(factor) + carry;
+            limbs.push(current as Limb);
+            carry = current >> LIMB_BITS;
+        }
+        limbs.push(carry as Limb);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        BigUint::from_limbs(vec![n as Limb, (n >> LIMB_BITS) as Limb])
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigUintError(s.to_string()));
+        }
+        let first = s.len() % DECIMAL_DIGITS;
+        let chunks = std::iter::once(&s[..first]).chain(
+            s.as_bytes()[first..]
+                .chunks(DECIMAL_DIGITS)
+                .map(|c| std::str::from_utf8(c).unwrap()),
+        );
+
+        Ok(chunks
+            .filter(|c| !c.is_empty())
+            .fold(BigUint::zero(), |n, c| {
+                n.mul_add_small(DECIMAL_BASE as u32, c.parse().unwrap())
+            }))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+        // Groups of 9 digits, least significant first
+        let mut groups = Vec::new();
+        let mut n = self.clone();
+        while !n.is_zero() {
+            let (q, r) = n.div_rem_small(DECIMAL_BASE as u32);
+            groups.push(r);
+            n = q;
+        }
+
+        let mut digits = groups.pop().unwrap().to_string();
+        for group in groups.iter().rev() {
+            digits += &format!("{group:0width$}", width = DECIMAL_DIGITS);
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let (long, short) = if self.limbs.len() >= other.limbs.len() {
+            (&self.limbs, &other.limbs)
+        } else {
+            (&other.limbs, &self.limbs)
+        };
+        let mut limbs = Vec::with_capacity(long.len() + 1);
+        let mut carry = 0;
+        for (i, &limb) in long.iter().enumerate() {
+            let other = short.get(i).copied().unwrap_or(0);
+            let sum = u64::from(limb) + u64::from(other) + carry;
+            limbs.push(sum as Limb);
+            carry = sum >> LIMB_BITS;
+        }
+        limbs.push(carry as Limb);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+/// Panics if the result would be negative
+impl Sub for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        assert!(self >= other, "BigUint subtraction underflow");
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = false;
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            let other = other.limbs.get(i).copied().unwrap_or(0);
+            let (diff, b1) = limb.overflowing_sub(other);
+            let (diff, b2) = diff.overflowing_sub(borrow as Limb);
+            limbs.push(diff);
+            borrow = b1 || b2;
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let current = u64::from(limbs[i + j])
+                    + u64::from(a) * u64::from(b)
+                    + carry;
+                limbs[i + j] = current as Limb;
+                carry = current >> LIMB_BITS;
+            }
+            limbs[i + other.limbs.len()] = carry as Limb;
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Div for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint { self.div_rem(other).0 }
+}
+
+impl Rem for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint { self.div_rem(other).1 }
+}
+
+/// Owned versions of the operators, so `BigUint` works with the generic code
+/// written for the primitive integers
+macro_rules! forward_owned_op {
+    ($($trait:ident $method:ident),*) => {
+        $(impl $trait for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                (&self).$method(&other)
+            }
+        })*
+    };
+}
+
+forward_owned_op!(Add add, Sub sub, Mul mul, Div div, Rem rem);
@@ -0,0 +1,222 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Cycle in the sequence `x0, f(x0), f(f(x0)), ...`: the element at index
+/// `start` (μ) is the first one that repeats, and it does so every `length`
+/// (λ) steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+/// Brent's cycle detection algorithm. Takes O(μ + λ) steps and only keeps two
+/// states in memory.
+pub fn brent<S: Clone + Eq>(start: S, step: impl FnMut(&S) -> S) -> Cycle {
+    brent_with(start, step, |a, b| a == b)
+}
+
+/// Like [`brent`] but two states are considered equal when their keys are.
+pub fn brent_by_key<S: Clone, K: Eq>(
+    start: S,
+    step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> Cycle {
+    brent_with(start, step, |a, b| key(a) == key(b))
+}
+
+fn brent_with<S: Clone>(
+    start: S,
+    mut f: impl FnMut(&S) -> S,
+    mut eq: impl FnMut(&S, &S) -> bool,
+) -> Cycle {
+    // Find λ by searching successive powers of two
+    let mut power = 1;
+    let mut lam = 1;
+    let mut tortoise = start.clone();
+    let mut hare = f(&start);
+
+    while !eq(&tortoise, &hare) {
+        if power == lam {
+            tortoise = hare.clone();
+            power *= 2;
+            lam = 0;
+        }
+        hare = f(&hare);
+        lam += 1;
+    }
+
+    // Find μ with the hare λ steps ahead of the tortoise
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..lam {
+        hare = f(&hare);
+    }
+
+    let mut mu = 0;
+    while !eq(&tortoise, &hare) {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        mu += 1;
+    }
+
+    Cycle {
+        start: mu,
+        length: lam,
+    }
+}
+
+/// Floyd's "tortoise and hare" cycle detection algorithm. Usually slower than
+/// [`brent`] but just as light on memory.
+pub fn floyd<S: Clone + Eq>(start: S, step: impl FnMut(&S) -> S) -> Cycle {
+    floyd_with(start, step, |a, b| a == b)
+}
+
+/// Like [`floyd`] but two states are considered equal when their keys are.
+pub fn floyd_by_key<S: Clone, K: Eq>(
+    start: S,
+    step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> Cycle {
+    floyd_with(start, step, |a, b| key(a) == key(b))
+}
+
+fn floyd_with<S: Clone>(
+    start: S,
+    mut f: impl FnMut(&S) -> S,
+    mut eq: impl FnMut(&S, &S) -> bool,
+) -> Cycle {
+    let mut tortoise = f(&start);
+    let mut hare = f(&tortoise);
+    while !eq(&tortoise, &hare) {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        hare = f(&hare);
+    }
+
+    let mut mu = 0;
+    let mut tortoise = start;
+    while !eq(&tortoise, &hare) {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        mu += 1;
+    }
+
+    let mut lam = 1;
+    let mut hare = f(&tortoise);
+    while !eq(&tortoise, &hare) {
+        hare = f(&hare);
+        lam += 1;
+    }
+
+    Cycle {
+        start: mu,
+        length: lam,
+    }
+}
+
+/// Cycle detection remembering the index of every key seen. Steps through the
+/// sequence only once (μ + λ steps) at the cost of O(μ + λ) memory, so it's the
+/// best option when stepping is expensive.
+pub fn hashed_by_key<S, K: Eq + Hash>(
+    start: S,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> Cycle {
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut state = start;
+
+    for i in 0.. {
+        if let Some(&first) = seen.get(&key(&state)) {
+            return Cycle {
+                start: first,
+                length: i - first,
+            };
+        }
+        seen.insert(key(&state), i);
+        state = step(&state);
+    }
+
+    unreachable!()
+}
+
+/// Quantity measured on each step of a simulation that can be added up.
+pub trait Metric {
+    /// Adds `other` into `self`
+    fn combine(&mut self, other: &Self);
+
+    /// Result of combining `self` with itself `times` times
+    fn repeat(&self, times: usize) -> Self;
+}
+
+macro_rules! impl_metric {
+    ($($t:ty),*) => {
+        $(impl Metric for $t {
+            fn combine(&mut self, other: &Self) { *self += *other; }
+
+            fn repeat(&self, times: usize) -> Self { *self * times as $t }
+        })*
+    };
+}
+
+impl_metric!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Element-wise, the shorter vector is extended with default values.
+impl<T: Metric + Default> Metric for Vec<T> {
+    fn combine(&mut self, other: &Self) {
+        if self.len() < other.len() {
+            self.resize_with(other.len(), Default::default);
+        }
+        for (m, o) in self.iter_mut().zip(other) {
+            m.combine(o);
+        }
+    }
+
+    fn repeat(&self, times: usize) -> Self {
+        self.iter().map(|m| m.repeat(times)).collect()
+    }
+}
+
+/// Runs `n` steps of a simulation starting from `start`, where `step` gives the
+/// next state and the metric measured during that step. Returns the final state
+/// and the metric combined over all steps.
+///
+/// The state is expected to eventually loop, after finding the cycle (with
+/// [`brent`]) the metric of a whole loop is measured once and then repeated,
+/// so the amount of steps actually simulated doesn't depend on `n`.
+pub fn simulate_n<S, M>(
+    start: S,
+    mut step: impl FnMut(&S) -> (S, M),
+    n: usize,
+) -> (S, M)
+where
+    S: Clone + Eq,
+    M: Metric + Default,
+{
+    let Cycle {
+        start: mu,
+        length: lam,
+    } = brent(start.clone(), |s| step(s).0);
+
+    let mut run = |state: &mut S, steps: usize, total: &mut M| {
+        for _ in 0..steps {
+            let (next, metric) = step(state);
+            total.combine(&metric);
+            *state = next;
+        }
+    };
+
+    let mut state = start;
+    let mut total = M::default();
+    if n <= mu {
+        run(&mut state, n, &mut total);
+        return (state, total);
+    }
+
+    run(&mut state, mu, &mut total);
+    let mut cycle = M::default();
+    run(&mut state, lam, &mut cycle);
+    total.combine(&cycle.repeat((n - mu) / lam));
+    run(&mut state, (n - mu) % lam, &mut total);
+
+    (state, total)
+}
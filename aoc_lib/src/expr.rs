@@ -0,0 +1,354 @@
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Sub},
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// Types closed under the four basic arithmetic operations.
+pub trait Arithmetic:
+    Sized
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+}
+
+impl<T> Arithmetic for T where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>
+{
+}
+
+/// Binary arithmetic operator shared by the expression based puzzles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Error, Debug)]
+#[error("'{0}' is not a valid operator (expected one of + - * /)")]
+pub struct ParseOperatorError(String);
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseExprError {
+    #[error("unexpected end of the expression")]
+    UnexpectedEnd,
+    #[error("unexpected '{found}' at column {column}")]
+    UnexpectedChar { found: char, column: usize },
+    #[error("'{0}' is not a valid number")]
+    InvalidNumber(String),
+    #[error("unknown variable '{0}'")]
+    UnknownVariable(String),
+}
+
+impl Operator {
+    pub fn apply<T>(self, lhs: T, rhs: T) -> T
+    where
+        T: Arithmetic,
+    {
+        match self {
+            Operator::Add => lhs + rhs,
+            Operator::Sub => lhs - rhs,
+            Operator::Mul => lhs * rhs,
+            Operator::Div => lhs / rhs,
+        }
+    }
+
+    /// Finds `lhs` such that `lhs <op> rhs == result`.
+    pub fn solve_lhs<T>(self, result: T, rhs: T) -> T
+    where
+        T: Arithmetic,
+    {
+        match self {
+            Operator::Add => result - rhs,
+            Operator::Sub => result + rhs,
+            Operator::Mul => result / rhs,
+            Operator::Div => result * rhs,
+        }
+    }
+
+    /// Finds `rhs` such that `lhs <op> rhs == result`.
+    pub fn solve_rhs<T>(self, result: T, lhs: T) -> T
+    where
+        T: Arithmetic,
+    {
+        match self {
+            Operator::Add => result - lhs,
+            Operator::Sub => lhs - result,
+            Operator::Mul => result / lhs,
+            Operator::Div => lhs / result,
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Operator::Add => '+',
+            Operator::Sub => '-',
+            Operator::Mul => '*',
+            Operator::Div => '/',
+        };
+        write!(f, "{c}")
+    }
+}
+
+impl FromStr for Operator {
+    type Err = ParseOperatorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "+" => Ok(Operator::Add),
+            "-" => Ok(Operator::Sub),
+            "*" => Ok(Operator::Mul),
+            "/" => Ok(Operator::Div),
+            other => Err(ParseOperatorError(other.to_string())),
+        }
+    }
+}
+
+/// Arithmetic expression tree over constants of type `T` and variables
+/// identified by `V`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr<T, V> {
+    Const(T),
+    Var(V),
+    BinOp(Operator, Box<Expr<T, V>>, Box<Expr<T, V>>),
+}
+
+impl<T, V> Expr<T, V> {
+    pub fn bin_op(op: Operator, lhs: Expr<T, V>, rhs: Expr<T, V>) -> Self {
+        Expr::BinOp(op, Box::new(lhs), Box::new(rhs))
+    }
+
+    /// Evaluates the expression, `lookup` gives the value of each variable.
+    pub fn eval(&self, lookup: &impl Fn(&V) -> T) -> T
+    where
+        T: Arithmetic + Clone,
+    {
+        match self {
+            Expr::Const(n) => n.clone(),
+            Expr::Var(v) => lookup(v),
+            Expr::BinOp(op, lhs, rhs) => {
+                op.apply(lhs.eval(lookup), rhs.eval(lookup))
+            }
+        }
+    }
+
+    /// Checks whether the variable `var` appears anywhere in the expression.
+    pub fn contains_var(&self, var: &V) -> bool
+    where
+        V: PartialEq,
+    {
+        match self {
+            Expr::Const(_) => false,
+            Expr::Var(v) => v == var,
+            Expr::BinOp(_, lhs, rhs) => {
+                lhs.contains_var(var) || rhs.contains_var(var)
+            }
+        }
+    }
+
+    /// Checks whether the operator `op` appears anywhere in the expression.
+    pub fn contains_op(&self, op: Operator) -> bool {
+        match self {
+            Expr::Const(_) | Expr::Var(_) => false,
+            Expr::BinOp(o, lhs, rhs) => {
+                *o == op || lhs.contains_op(op) || rhs.contains_op(op)
+            }
+        }
+    }
+}
+
+impl<T: FromStr, V> Expr<T, V> {
+    /// Parses an infix expression like `old * (old + 3) / 2`: numbers,
+    /// variables (`var` gives the one for each name, `None` if it's unknown),
+    /// the four operators with the usual precedence and parentheses.
+    pub fn parse<'a>(
+        s: &'a str,
+        var: impl FnMut(&'a str) -> Option<V>,
+    ) -> Result<Self, ParseExprError> {
+        let mut parser = ExprParser { s, pos: 0, var };
+        let expr = parser.sum()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(c) => Err(parser.unexpected(c)),
+        }
+    }
+}
+
+/// Recursive descent parser behind [`Expr::parse`], one method per
+/// precedence level.
+struct ExprParser<'a, F> {
+    s: &'a str,
+    pos: usize,
+    var: F,
+}
+
+impl<'a, F> ExprParser<'a, F> {
+    /// Terms joined by `+` and `-`
+    fn sum<T: FromStr, V>(&mut self) -> Result<Expr<T, V>, ParseExprError>
+    where
+        F: FnMut(&'a str) -> Option<V>,
+    {
+        let mut lhs = self.product()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.product()?;
+            lhs = Expr::bin_op(Self::operator(op), lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    /// Atoms joined by `*` and `/`
+    fn product<T: FromStr, V>(&mut self) -> Result<Expr<T, V>, ParseExprError>
+    where
+        F: FnMut(&'a str) -> Option<V>,
+    {
+        let mut lhs = self.atom()?;
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.atom()?;
+            lhs = Expr::bin_op(Self::operator(op), lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    /// Number, variable or parenthesized expression
+    fn atom<T: FromStr, V>(&mut self) -> Result<Expr<T, V>, ParseExprError>
+    where
+        F: FnMut(&'a str) -> Option<V>,
+    {
+        match self.peek().ok_or(ParseExprError::UnexpectedEnd)? {
+            '(' => {
+                self.pos += 1;
+                let expr = self.sum()?;
+                match self.peek() {
+                    Some(')') => {
+                        self.pos += 1;
+                        Ok(expr)
+                    }
+                    Some(c) => Err(self.unexpected(c)),
+                    None => Err(ParseExprError::UnexpectedEnd),
+                }
+            }
+            c if c.is_ascii_digit() => {
+                let num = self.take_while(|c| c.is_ascii_alphanumeric());
+                num.parse()
+                    .map(Expr::Const)
+                    .map_err(|_| ParseExprError::InvalidNumber(num.to_string()))
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+                (self.var)(name)
+                    .map(Expr::Var)
+                    .ok_or_else(|| ParseExprError::UnknownVariable(name.into()))
+            }
+            c => Err(self.unexpected(c)),
+        }
+    }
+
+    /// Next character that isn't whitespace, without consuming it
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.s[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+        self.s[self.pos..].chars().next()
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let rest = &self.s[self.pos..];
+        let len = rest.find(|c| !f(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn unexpected(&self, found: char) -> ParseExprError {
+        ParseExprError::UnexpectedChar {
+            found,
+            column: self.pos,
+        }
+    }
+
+    fn operator(c: char) -> Operator {
+        match c {
+            '+' => Operator::Add,
+            '-' => Operator::Sub,
+            '*' => Operator::Mul,
+            '/' => Operator::Div,
+            _ => unreachable!("'{c}' isn't an operator"),
+        }
+    }
+}
+
+impl<T: fmt::Display, V: fmt::Display> fmt::Display for Expr<T, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Const(n) => write!(f, "{n}"),
+            Expr::Var(v) => write!(f, "{v}"),
+            Expr::BinOp(op, lhs, rhs) => write!(f, "({lhs} {op} {rhs})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Expr<i64, char>, ParseExprError> {
+        Expr::parse(s, |name| (name == "x").then_some('x'))
+    }
+
+    /// Parses `s`, checks the fully parenthesized form of the tree and
+    /// evaluates it with `x = 7`
+    fn check(s: &str, tree: &str, value: i64) {
+        let expr = parse(s).unwrap();
+        assert_eq!(expr.to_string(), tree, "{s}");
+        assert_eq!(expr.eval(&|_| 7), value, "{s}");
+    }
+
+    #[test]
+    fn precedence() {
+        check("1 + 2 * 3", "(1 + (2 * 3))", 7);
+        check("1 * 2 + 3", "((1 * 2) + 3)", 5);
+        check("x * x - 10 / 5", "((x * x) - (10 / 5))", 47);
+        // Same precedence goes left to right
+        check("10 - 4 - 3", "((10 - 4) - 3)", 3);
+        check("24 / 4 / 2", "((24 / 4) / 2)", 3);
+        check("x", "x", 7);
+    }
+
+    #[test]
+    fn parentheses() {
+        check("(1 + 2) * 3", "((1 + 2) * 3)", 9);
+        check("10 - (4 - 3)", "(10 - (4 - 3))", 9);
+        check("((x))", "x", 7);
+        check(" ( x+1 )*( x - 1 ) ", "((x + 1) * (x - 1))", 48);
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        use ParseExprError::*;
+
+        let unexpected = |found, column| UnexpectedChar { found, column };
+        let cases = [
+            ("", UnexpectedEnd),
+            ("1 +", UnexpectedEnd),
+            ("(1 + 2", UnexpectedEnd),
+            ("()", unexpected(')', 1)),
+            ("1 + 2)", unexpected(')', 5)),
+            ("1 $ 2", unexpected('$', 2)),
+            ("2 3", unexpected('3', 2)),
+            ("* 2", unexpected('*', 0)),
+            ("(1 + 2]", unexpected(']', 6)),
+            ("12ab", InvalidNumber("12ab".to_string())),
+            ("x + old", UnknownVariable("old".to_string())),
+        ];
+        for (s, err) in cases {
+            assert_eq!(parse(s), Err(err), "{s:?}");
+        }
+    }
+}
@@ -0,0 +1,493 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+/// Index of a node in a [`Graph`]
+pub type NodeId = usize;
+
+/// Directed graph stored as adjacency lists, nodes are referred to by the
+/// index they were added with. Undirected graphs just add every edge both ways.
+#[derive(Debug, Clone)]
+pub struct Graph<N, E> {
+    nodes: Vec<N>,
+    edges: Vec<Vec<(NodeId, E)>>,
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn new() -> Graph<N, E> {
+        Graph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Graph with the given nodes and `(from, to, edge)` triples
+    pub fn from_edges(
+        nodes: impl IntoIterator<Item = N>,
+        edges: impl IntoIterator<Item = (NodeId, NodeId, E)>,
+    ) -> Graph<N, E> {
+        let mut graph = Graph::new();
+        for node in nodes {
+            graph.add_node(node);
+        }
+        for (from, to, edge) in edges {
+            graph.add_edge(from, to, edge);
+        }
+        graph
+    }
+
+    pub fn add_node(&mut self, node: N) -> NodeId {
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        self.nodes.len() - 1
+    }
+
+    /// Panics if either node doesn't exist
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, edge: E) {
+        assert!(
+            to < self.nodes.len(),
+            "Edge to node {to}, but the graph has {} nodes",
+            self.nodes.len()
+        );
+        self.edges[from].push((to, edge));
+    }
+
+    /// Adds the edge in both directions
+    pub fn add_undirected_edge(&mut self, a: NodeId, b: NodeId, edge: E)
+    where
+        E: Clone,
+    {
+        self.add_edge(a, b, edge.clone());
+        self.add_edge(b, a, edge);
+    }
+
+    /// Amount of nodes
+    pub fn len(&self) -> usize { self.nodes.len() }
+
+    pub fn is_empty(&self) -> bool { self.nodes.is_empty() }
+
+    pub fn edge_count(&self) -> usize { self.edges.iter().map(Vec::len).sum() }
+
+    pub fn node(&self, id: NodeId) -> &N { &self.nodes[id] }
+
+    pub fn node_mut(&mut self, id: NodeId) -> &mut N { &mut self.nodes[id] }
+
+    /// Every node with its id
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &N)> {
+        self.nodes.iter().enumerate()
+    }
+
+    /// Outgoing edges of `from`, with their destination
+    pub fn edges(&self, from: NodeId) -> &[(NodeId, E)] { &self.edges[from] }
+
+    /// Destinations of the outgoing edges of `from`
+    pub fn neighbors(&self, from: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges[from].iter().map(|&(to, _)| to)
+    }
+
+    /// Order of the nodes where every edge goes forward (Kahn's algorithm), or
+    /// `None` if the graph has a cycle.
+    pub fn topological_sort(&self) -> Option<Vec<NodeId>> {
+        let mut incoming = vec![0; self.len()];
+        for to in (0..self.len()).flat_map(|from| self.neighbors(from)) {
+            incoming[to] += 1;
+        }
+
+        let mut ready: VecDeque<NodeId> =
+            (0..self.len()).filter(|&id| incoming[id] == 0).collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(id) = ready.pop_front() {
+            order.push(id);
+            for to in self.neighbors(id) {
+                incoming[to] -= 1;
+                if incoming[to] == 0 {
+                    ready.push_back(to);
+                }
+            }
+        }
+
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Distance from `start` to every node (Dijkstra), `None` for the ones
+    /// that can't be reached. Paths longer than `u64::MAX` are ignored, so
+    /// nodes only reachable through them count as unreachable.
+    pub fn shortest_paths_from(
+        &self,
+        start: NodeId,
+        weight: impl Fn(&E) -> u64,
+    ) -> Vec<Option<u64>> {
+        let mut dist = vec![None; self.len()];
+        let mut heap = BinaryHeap::from([Reverse((0u64, start))]);
+
+        while let Some(Reverse((d, id))) = heap.pop() {
+            if dist[id].is_some() {
+                continue;
+            }
+            dist[id] = Some(d);
+            for (to, edge) in &self.edges[id] {
+                let next = d.checked_add(weight(edge));
+                if let (None, Some(next)) = (dist[*to], next) {
+                    heap.push(Reverse((next, *to)));
+                }
+            }
+        }
+
+        dist
+    }
+
+    /// Shortest distance between every pair of nodes (Floyd–Warshall). Like
+    /// [`Graph::shortest_paths_from`], paths longer than `u64::MAX` are
+    /// ignored.
+    pub fn all_pairs_shortest_paths(
+        &self,
+        weight: impl Fn(&E) -> u64,
+    ) -> Distances {
+        let n = self.len();
+        let mut dist = Distances {
+            nodes: n,
+            dist: vec![None; n * n],
+        };
+        for from in 0..n {
+            dist.improve(from, from, 0);
+            for (to, edge) in &self.edges[from] {
+                dist.improve(from, *to, weight(edge));
+            }
+        }
+
+        for k in 0..n {
+            for i in 0..n {
+                let Some(ik) = dist.get(i, k) else { continue };
+                for j in 0..n {
+                    let kj = dist.get(k, j);
+                    if let Some(d) = kj.and_then(|kj| ik.checked_add(kj)) {
+                        dist.improve(i, j, d);
+                    }
+                }
+            }
+        }
+
+        dist
+    }
+
+    /// Graph of only the nodes for which `keep` holds, with an edge between
+    /// every pair of them weighted by their shortest distance in this graph.
+    /// Returns it with the ids the kept nodes had here.
+    pub fn compress(
+        &self,
+        mut keep: impl FnMut(NodeId, &N) -> bool,
+        weight: impl Fn(&E) -> u64,
+    ) -> (Graph<N, u64>, Vec<NodeId>)
+    where
+        N: Clone,
+    {
+        let kept: Vec<NodeId> = self
+            .nodes()
+            .filter(|&(id, n)| keep(id, n))
+            .map(|(id, _)| id)
+            .collect();
+
+        let mut graph = Graph::new();
+        for &id in &kept {
+            graph.add_node(self.nodes[id].clone());
+        }
+        for (from, &id) in kept.iter().enumerate() {
+            let dist = self.shortest_paths_from(id, &weight);
+            for (to, &other) in kept.iter().enumerate() {
+                if let (true, Some(d)) = (from != to, dist[other]) {
+                    graph.add_edge(from, to, d);
+                }
+            }
+        }
+
+        (graph, kept)
+    }
+
+    /// Nodes grouped by the (weakly) connected component they're in
+    pub fn components(&self) -> DisjointSets {
+        let mut sets = DisjointSets::new(self.len());
+        for from in 0..self.len() {
+            for to in self.neighbors(from) {
+                sets.union(from, to);
+            }
+        }
+        sets
+    }
+}
+
+impl<E> Graph<String, E> {
+    /// Graph from `(from, to, edge)` triples of node labels, every label
+    /// becomes a node the first time it's seen.
+    pub fn from_labeled_edges<'a>(
+        edges: impl IntoIterator<Item = (&'a str, &'a str, E)>,
+    ) -> (Graph<String, E>, Labels) {
+        let mut graph = Graph::new();
+        let mut labels = Labels::new();
+        for (from, to, edge) in edges {
+            let from = labels.node(&mut graph, from);
+            let to = labels.node(&mut graph, to);
+            graph.add_edge(from, to, edge);
+        }
+        (graph, labels)
+    }
+}
+
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Self { Graph::new() }
+}
+
+/// Ids of the nodes of a graph whose nodes are named by strings
+#[derive(Debug, Clone, Default)]
+pub struct Labels {
+    ids: HashMap<String, NodeId>,
+}
+
+impl Labels {
+    pub fn new() -> Labels { Labels::default() }
+
+    /// Id of the node named `label`, added to `graph` if it isn't there yet
+    pub fn node<E>(
+        &mut self,
+        graph: &mut Graph<String, E>,
+        label: &str,
+    ) -> NodeId {
+        if let Some(&id) = self.ids.get(label) {
+            return id;
+        }
+        let id = graph.add_node(label.to_owned());
+        self.ids.insert(label.to_owned(), id);
+        id
+    }
+
+    pub fn get(&self, label: &str) -> Option<NodeId> {
+        self.ids.get(label).copied()
+    }
+}
+
+/// Distances between every pair of nodes, see
+/// [`Graph::all_pairs_shortest_paths`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distances {
+    nodes: usize,
+    dist: Vec<Option<u64>>,
+}
+
+impl Distances {
+    /// `None` if `to` can't be reached from `from`
+    pub fn get(&self, from: NodeId, to: NodeId) -> Option<u64> {
+        self.dist[from * self.nodes + to]
+    }
+
+    fn improve(&mut self, from: NodeId, to: NodeId, d: u64) {
+        let current = &mut self.dist[from * self.nodes + to];
+        if current.is_none_or(|c| d < c) {
+            *current = Some(d);
+        }
+    }
+}
+
+/// Disjoint-set union (union-find) over the elements `0..n`, with path
+/// compression and union by size.
+#[derive(Debug, Clone)]
+pub struct DisjointSets {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    sets: usize,
+}
+
+impl DisjointSets {
+    /// Every element in a set of its own
+    pub fn new(n: usize) -> DisjointSets {
+        DisjointSets {
+            parents: (0..n).collect(),
+            sizes: vec![1; n],
+            sets: n,
+        }
+    }
+
+    /// Amount of elements
+    pub fn len(&self) -> usize { self.parents.len() }
+
+    pub fn is_empty(&self) -> bool { self.parents.is_empty() }
+
+    /// Amount of disjoint sets
+    pub fn set_count(&self) -> usize { self.sets }
+
+    /// Representative of the set `x` is in
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut current = x;
+        while current != root {
+            current = std::mem::replace(&mut self.parents[current], root);
+        }
+        root
+    }
+
+    /// Merges the sets of `a` and `b`, returns whether they were different.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) =
+            if self.sizes[a] >= self.sizes[b] { (a, b) } else { (b, a) };
+        self.parents[small] = big;
+        self.sizes[big] += self.sizes[small];
+        self.sets -= 1;
+        true
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Amount of elements in the set `x` is in
+    pub fn set_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /// Elements grouped by set, each group in ascending order
+    pub fn sets(&mut self) -> Vec<Vec<usize>> {
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+        for x in 0..self.len() {
+            groups.entry(self.find(x)).or_default().push(x);
+        }
+        let mut groups: Vec<_> = groups.into_values().collect();
+        groups.sort_unstable();
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The valves of the day 16 example with their flow rates and tunnels
+    const VALVES: [(&str, u64, &[&str]); 10] = [
+        ("AA", 0, &["DD", "II", "BB"]),
+        ("BB", 13, &["CC", "AA"]),
+        ("CC", 2, &["DD", "BB"]),
+        ("DD", 20, &["CC", "AA", "EE"]),
+        ("EE", 3, &["FF", "DD"]),
+        ("FF", 0, &["EE", "GG"]),
+        ("GG", 0, &["FF", "HH"]),
+        ("HH", 22, &["GG"]),
+        ("II", 0, &["AA", "JJ"]),
+        ("JJ", 21, &["II"]),
+    ];
+
+    /// Only the start and the valves with some flow, and the minutes it takes
+    /// to walk between them
+    #[test]
+    fn compress_valves() {
+        let tunnels = VALVES.iter().flat_map(|&(from, _, to)| {
+            to.iter().map(move |&to| (from, to, ()))
+        });
+        let (graph, labels) = Graph::from_labeled_edges(tunnels);
+        assert_eq!((graph.len(), graph.edge_count()), (10, 20));
+        let jj = labels.get("JJ").unwrap();
+        assert_eq!(graph.node(jj), "JJ");
+
+        let rate =
+            |label: &str| VALVES.iter().find(|v| v.0 == label).unwrap().1;
+        let (valves, kept) =
+            graph.compress(|_, label| label == "AA" || rate(label) > 0, |_| 1);
+        let names: Vec<&str> =
+            valves.nodes().map(|(_, n)| n.as_str()).collect();
+        // In the order their labels first show up in the tunnels
+        assert_eq!(names, ["AA", "DD", "BB", "CC", "EE", "HH", "JJ"]);
+        let expected = [
+            ("AA", [0, 1, 1, 2, 2, 5, 2]),
+            ("DD", [1, 0, 2, 1, 1, 4, 3]),
+            ("BB", [1, 2, 0, 1, 3, 6, 3]),
+            ("CC", [2, 1, 1, 0, 2, 5, 4]),
+            ("EE", [2, 1, 3, 2, 0, 3, 4]),
+            ("HH", [5, 4, 6, 5, 3, 0, 7]),
+            ("JJ", [2, 3, 3, 4, 4, 7, 0]),
+        ];
+        assert_eq!(valves.edge_count(), 7 * 6);
+        let all_pairs = graph.all_pairs_shortest_paths(|_| 1);
+        for (from, (label, dists)) in expected.iter().enumerate() {
+            assert_eq!(graph.node(kept[from]), label);
+            for (to, &d) in dists.iter().enumerate() {
+                let edge = valves.edges(from).iter().find(|e| e.0 == to);
+                assert_eq!(edge.map(|e| e.1), (from != to).then_some(d));
+                assert_eq!(all_pairs.get(kept[from], kept[to]), Some(d));
+            }
+        }
+    }
+
+    #[test]
+    fn shortest_paths() {
+        let graph = Graph::from_edges(
+            0..5,
+            [(0, 1, 4), (0, 2, 1), (2, 1, 2), (1, 3, 1), (3, 0, 7)],
+        );
+        let from_0 = [Some(0), Some(3), Some(1), Some(4), None];
+        assert_eq!(graph.shortest_paths_from(0, |&w| w), from_0);
+        let from_3 = [Some(7), Some(10), Some(8), Some(0), None];
+        assert_eq!(graph.shortest_paths_from(3, |&w| w), from_3);
+
+        let all_pairs = graph.all_pairs_shortest_paths(|&w| w);
+        for (start, expected) in [(0, from_0), (3, from_3)] {
+            for (to, d) in expected.into_iter().enumerate() {
+                assert_eq!(all_pairs.get(start, to), d, "{start} to {to}");
+            }
+        }
+        assert_eq!(all_pairs.get(4, 4), Some(0));
+        assert_eq!(all_pairs.get(4, 0), None);
+    }
+
+    /// Paths that would overflow are left out instead of wrapping around
+    #[test]
+    fn shortest_paths_overflow() {
+        let graph = Graph::from_edges(0..3, [(0, 1, u64::MAX), (1, 2, 1)]);
+        let dist = graph.shortest_paths_from(0, |&w| w);
+        assert_eq!(dist, [Some(0), Some(u64::MAX), None]);
+        let all_pairs = graph.all_pairs_shortest_paths(|&w| w);
+        assert_eq!(all_pairs.get(0, 1), Some(u64::MAX));
+        assert_eq!(all_pairs.get(0, 2), None);
+        assert_eq!(all_pairs.get(1, 2), Some(1));
+    }
+
+    #[test]
+    fn topological_sort() {
+        let mut graph =
+            Graph::from_edges(0..5, [(0, 2, ()), (1, 2, ()), (2, 3, ())]);
+        graph.add_edge(1, 4, ());
+        graph.add_edge(4, 3, ());
+        assert_eq!(graph.topological_sort(), Some(vec![0, 1, 2, 4, 3]));
+
+        graph.add_edge(3, 1, ());
+        assert_eq!(graph.topological_sort(), None);
+
+        let self_loop = Graph::from_edges(0..2, [(0, 1, ()), (1, 1, ())]);
+        assert_eq!(self_loop.topological_sort(), None);
+        assert_eq!(Graph::<(), ()>::new().topological_sort(), Some(vec![]));
+    }
+
+    #[test]
+    fn disjoint_sets() {
+        let mut sets = DisjointSets::new(6);
+        assert_eq!(sets.set_count(), 6);
+        assert!(sets.union(0, 1));
+        assert!(!sets.union(1, 0));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(3, 1));
+
+        assert_eq!(sets.set_count(), 3);
+        assert_eq!(sets.set_size(2), 4);
+        assert_eq!(sets.set_size(5), 1);
+        assert!(sets.same_set(0, 3));
+        assert!(!sets.same_set(0, 4));
+        assert_eq!(sets.sets(), [vec![0, 1, 2, 3], vec![4], vec![5]]);
+
+        let graph = Graph::from_edges(0..4, [(0, 1, ()), (3, 1, ())]);
+        assert_eq!(graph.components().sets(), [vec![0, 1, 3], vec![2]]);
+    }
+}
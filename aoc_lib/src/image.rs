@@ -0,0 +1,208 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+use crate::structs::grid::Grid;
+
+/// Red, green and blue components of a pixel
+pub type Rgb = [u8; 3];
+
+#[derive(Error, Debug)]
+pub enum ImageError {
+    #[error("couldn't write the image: {0}")]
+    Io(#[from] io::Error),
+    #[error("couldn't encode the PNG: {0}")]
+    Png(#[from] png::EncodingError),
+    #[error("unknown image format for '{0}' (expected .ppm or .png)")]
+    UnknownFormat(PathBuf),
+    #[error("images need a scale of at least 1")]
+    ZeroScale,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Binary (P6) portable pixmap, trivial to write and read by most viewers
+    Ppm,
+    Png,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+
+    /// Format matching the extension of `path`
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        match path.extension()?.to_str()? {
+            "ppm" => Some(ImageFormat::Ppm),
+            "png" => Some(ImageFormat::Png),
+            _ => None,
+        }
+    }
+}
+
+/// RGB image of a grid, where every tile is a `scale`×`scale` square.
+#[derive(Debug, Clone)]
+pub struct GridImage {
+    width: usize,
+    height: usize,
+    /// Row by row, 3 bytes per pixel
+    pixels: Vec<u8>,
+}
+
+impl GridImage {
+    /// Panics if `scale` is 0
+    pub fn new<T>(
+        grid: &Grid<T>,
+        scale: usize,
+        color: impl Fn(&T) -> Rgb,
+    ) -> GridImage {
+        assert!(scale > 0, "Images need a scale of at least 1");
+        let width = grid.columns() * scale;
+        let height = grid.rows() * scale;
+        let mut pixels = Vec::with_capacity(width * height * 3);
+
+        if width > 0 {
+            for row in grid.iter_rows() {
+                let line = pixels.len()..pixels.len() + width * 3;
+                for tile in row {
+                    let rgb = color(tile);
+                    for _ in 0..scale {
+                        pixels.extend_from_slice(&rgb);
+                    }
+                }
+                for _ in 1..scale {
+                    pixels.extend_from_within(line.clone());
+                }
+            }
+        }
+
+        GridImage {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize { self.width }
+
+    pub fn height(&self) -> usize { self.height }
+
+    pub fn write_ppm(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.pixels)?;
+        writer.flush()
+    }
+
+    pub fn write_png(&self, writer: impl Write) -> Result<(), ImageError> {
+        let mut encoder =
+            png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    pub fn write(
+        &self,
+        writer: impl Write,
+        format: ImageFormat,
+    ) -> Result<(), ImageError> {
+        match format {
+            ImageFormat::Ppm => Ok(self.write_ppm(writer)?),
+            ImageFormat::Png => self.write_png(writer),
+        }
+    }
+
+    /// Writes the image to a file, in the format given by its extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ImageError> {
+        let path = path.as_ref();
+        let format = ImageFormat::from_path(path)
+            .ok_or_else(|| ImageError::UnknownFormat(path.to_owned()))?;
+
+        self.write(BufWriter::new(File::create(path)?), format)
+    }
+}
+
+/// Writes the states of a simulation as numbered images (`prefix00000.png`,
+/// `prefix00001.png`...), which can be turned into an animation with the usual
+/// tools (e.g. `ffmpeg -i frame%05d.png sim.mp4`).
+#[derive(Debug)]
+pub struct FrameWriter {
+    dir: PathBuf,
+    prefix: String,
+    format: ImageFormat,
+    scale: usize,
+    frame: usize,
+}
+
+impl FrameWriter {
+    /// Creates `dir` if it doesn't exist. Fails with [`ImageError::ZeroScale`]
+    /// if `scale` is 0, before touching the filesystem.
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        prefix: &str,
+        format: ImageFormat,
+        scale: usize,
+    ) -> Result<FrameWriter, ImageError> {
+        if scale == 0 {
+            return Err(ImageError::ZeroScale);
+        }
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        Ok(FrameWriter {
+            dir,
+            prefix: prefix.to_owned(),
+            format,
+            scale,
+            frame: 0,
+        })
+    }
+
+    /// Amount of frames written so far
+    pub fn frames(&self) -> usize { self.frame }
+
+    /// Writes `grid` as the next frame, returns the path of the image.
+    pub fn write<T>(
+        &mut self,
+        grid: &Grid<T>,
+        color: impl Fn(&T) -> Rgb,
+    ) -> Result<PathBuf, ImageError> {
+        let name = format!(
+            "{}{:05}.{}",
+            self.prefix,
+            self.frame,
+            self.format.extension()
+        );
+        let path = self.dir.join(name);
+
+        let image = GridImage::new(grid, self.scale, color);
+        image.write(BufWriter::new(File::create(&path)?), self.format)?;
+        self.frame += 1;
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_writer_rejects_zero_scale() {
+        let dir = std::env::temp_dir().join("aoc_frames_zero_scale");
+        let frames = FrameWriter::new(&dir, "frame", ImageFormat::Png, 0);
+        assert!(matches!(frames, Err(ImageError::ZeroScale)));
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,127 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+    iter::Sum,
+};
+
+/// Extra iterator adapters and consumers that keep coming up in the puzzles.
+/// Implemented for every iterator, so it's enough to bring it into scope.
+pub trait AocIterExt: Iterator + Sized {
+    /// Greatest `k` elements in descending order, keeps a heap of at most `k`
+    /// elements instead of sorting everything.
+    fn top_k(self, k: usize) -> Vec<Self::Item>
+    where
+        Self::Item: Ord,
+    {
+        let mut heap: BinaryHeap<Reverse<Self::Item>> =
+            BinaryHeap::with_capacity(k + 1);
+        for item in self {
+            heap.push(Reverse(item));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        heap.into_sorted_vec().into_iter().map(|r| r.0).collect()
+    }
+
+    /// Minimum and maximum elements found in a single pass, `None` if the
+    /// iterator is empty. On ties the first minimum and last maximum are kept.
+    fn min_max(mut self) -> Option<(Self::Item, Self::Item)>
+    where
+        Self::Item: Ord + Clone,
+    {
+        let first = self.next()?;
+        Some(self.fold((first.clone(), first), |(min, max), item| {
+            if item < min {
+                (item, max)
+            } else if item >= max {
+                (min, item)
+            } else {
+                (min, max)
+            }
+        }))
+    }
+
+    /// Amount of times each element appears
+    fn counts(self) -> HashMap<Self::Item, usize>
+    where
+        Self::Item: Eq + Hash,
+    {
+        let mut counts = HashMap::new();
+        for item in self {
+            *counts.entry(item).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Sum of `f` applied to every element
+    fn sum_by<S: Sum, F: FnMut(Self::Item) -> S>(self, f: F) -> S {
+        self.map(f).sum()
+    }
+
+    /// First element that is equal to a previous one
+    fn find_duplicate(mut self) -> Option<Self::Item>
+    where
+        Self::Item: Eq + Hash + Clone,
+    {
+        let mut seen = HashSet::new();
+        self.find(|item| !seen.insert(item.clone()))
+    }
+
+    /// Whether no element is repeated
+    fn all_distinct(self) -> bool
+    where
+        Self::Item: Eq + Hash + Clone,
+    {
+        self.find_duplicate().is_none()
+    }
+
+    /// Groups the elements between the ones matching `separator` (which are
+    /// dropped). Behaves like [`slice::split`], so consecutive separators give
+    /// empty groups. Useful for blank line separated groups of lines.
+    fn split_on<P>(self, separator: P) -> SplitOn<Self, P>
+    where
+        P: FnMut(&Self::Item) -> bool,
+    {
+        SplitOn {
+            iter: self,
+            separator,
+            finished: false,
+        }
+    }
+}
+
+impl<I: Iterator> AocIterExt for I {}
+
+/// Iterator returned by [`AocIterExt::split_on`]
+pub struct SplitOn<I, P> {
+    iter: I,
+    separator: P,
+    finished: bool,
+}
+
+impl<I, P> Iterator for SplitOn<I, P>
+where
+    I: Iterator,
+    P: FnMut(&I::Item) -> bool,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let mut group = Vec::new();
+        for item in self.iter.by_ref() {
+            if (self.separator)(&item) {
+                return Some(group);
+            }
+            group.push(item);
+        }
+
+        self.finished = true;
+        Some(group)
+    }
+}
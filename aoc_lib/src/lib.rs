@@ -1,6 +1,16 @@
 #![allow(dead_code)]
 
 pub mod benchmark;
+pub mod bigint;
+pub mod cycle;
+pub mod expr;
+pub mod graph;
+pub mod image;
+pub mod iter;
+pub mod math;
+pub mod ocr;
+pub mod random;
+pub mod search;
 pub mod structs;
 pub mod tooling;
 pub mod parsing;
@@ -0,0 +1,365 @@
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Rem, Sub},
+    str::FromStr,
+};
+
+/// Primitive integer types, so the number theory helpers work with any of them
+pub trait Integer:
+    Copy
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Greatest common divisor (Euclid's algorithm), expects non-negative inputs.
+pub fn gcd<T: Integer>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, expects non-negative inputs.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    a / gcd(a, b) * b
+}
+
+/// GCD of all the numbers, 0 if there's none.
+pub fn gcd_all<T: Integer>(nums: impl IntoIterator<Item = T>) -> T {
+    nums.into_iter().fold(T::ZERO, gcd)
+}
+
+/// LCM of all the numbers, 1 if there's none.
+pub fn lcm_all<T: Integer>(nums: impl IntoIterator<Item = T>) -> T {
+    nums.into_iter().fold(T::ONE, lcm)
+}
+
+/// Returns `(g, x, y)` such that `a*x + b*y = g = gcd(a, b)`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Inverse of `a` modulo `m` in `0..m`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// `a * b % m` without overflowing.
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// `base^exp % m` by repeated squaring.
+pub fn pow_mod(base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Chinese remainder theorem: solves the system `x ≡ residue (mod modulus)`
+/// for every `(residue, modulus)` given. The moduli don't need to be coprime.
+/// Returns `(x, lcm of the moduli)` with `x` in `0..lcm`, or `None` if the
+/// congruences contradict each other.
+pub fn crt(
+    congruences: impl IntoIterator<Item = (i128, i128)>,
+) -> Option<(i128, i128)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(x, m), (residue, modulus)| {
+            let (g, p, _) = extended_gcd(m, modulus);
+            let diff = residue - x;
+            if diff % g != 0 {
+                return None;
+            }
+            let lcm = m / g * modulus;
+            let step = (diff / g).rem_euclid(modulus / g) * p % (modulus / g);
+            Some(((x + m * step).rem_euclid(lcm), lcm))
+        })
+}
+
+/// Integer modulo `M`, every operation is reduced so it never grows past it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modular<const M: u64>(u64);
+
+impl<const M: u64> Modular<M> {
+    pub fn new(n: u64) -> Self { Modular(n % M) }
+
+    pub fn value(self) -> u64 { self.0 }
+
+    pub fn pow(self, exp: u64) -> Self { Modular(pow_mod(self.0, exp, M)) }
+
+    /// Multiplicative inverse, if it exists (`self` and `M` are coprime).
+    pub fn inverse(self) -> Option<Self> {
+        mod_inverse(self.0.into(), M.into()).map(|n| Modular(n as u64))
+    }
+}
+
+impl<const M: u64> Add for Modular<M> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let (sum, overflow) = self.0.overflowing_add(other.0);
+        if overflow || sum >= M {
+            Modular(sum.wrapping_sub(M))
+        } else {
+            Modular(sum)
+        }
+    }
+}
+
+impl<const M: u64> Sub for Modular<M> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        if self.0 >= other.0 {
+            Modular(self.0 - other.0)
+        } else {
+            Modular(M - (other.0 - self.0))
+        }
+    }
+}
+
+impl<const M: u64> Mul for Modular<M> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        // Small moduli don't need the (much slower) 128 bit product
+        if M <= u32::MAX as u64 {
+            Modular(self.0 * other.0 % M)
+        } else {
+            Modular(mul_mod(self.0, other.0, M))
+        }
+    }
+}
+
+/// Multiplication by the inverse, panics if `other` isn't invertible.
+impl<const M: u64> Div for Modular<M> {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let inverse = other
+            .inverse()
+            .unwrap_or_else(|| panic!("{} has no inverse modulo {M}", other.0));
+        Modular(mul_mod(self.0, inverse.0, M))
+    }
+}
+
+/// Remainder of the represented value, it's only meaningful when `other`
+/// divides `M` (then `a % other` is the same for every `a` in the class).
+impl<const M: u64> Rem for Modular<M> {
+    type Output = Self;
+
+    fn rem(self, other: Self) -> Self {
+        debug_assert!(
+            M.is_multiple_of(other.0),
+            "{} doesn't divide {M}",
+            other.0
+        );
+        Modular(self.0 % other.0)
+    }
+}
+
+impl<const M: u64> From<u64> for Modular<M> {
+    fn from(n: u64) -> Self { Modular::new(n) }
+}
+
+impl<const M: u64> FromStr for Modular<M> {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Modular::new(s.parse()?))
+    }
+}
+
+impl<const M: u64> fmt::Display for Modular<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Like [`Modular`] but with the modulus chosen at runtime. Values without one
+/// (parsed or [`Default`]) are plain integers until an operation combines them
+/// with a reduced value, so constants can be read before the modulus is known.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ModN {
+    value: u64,
+    modulus: Option<u64>,
+}
+
+impl ModN {
+    pub fn new(n: u64, modulus: u64) -> Self {
+        ModN {
+            value: n % modulus,
+            modulus: Some(modulus),
+        }
+    }
+
+    pub fn value(self) -> u64 { self.value }
+
+    pub fn modulus(self) -> Option<u64> { self.modulus }
+
+    /// Modulus of the result of an operation on both, panics if they have
+    /// different ones
+    fn common_modulus(self, other: Self) -> Option<u64> {
+        match (self.modulus, other.modulus) {
+            (Some(a), Some(b)) if a != b => {
+                panic!("Operation between values modulo {a} and {b}")
+            }
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Applies `reduced` to both values modulo `m` if there's one, `plain` to
+    /// the values as they are otherwise
+    fn combine(
+        self,
+        other: Self,
+        reduced: impl Fn(u64, u64, u64) -> u64,
+        plain: impl Fn(u64, u64) -> u64,
+    ) -> Self {
+        match self.common_modulus(other) {
+            Some(m) => ModN {
+                value: reduced(self.value % m, other.value % m, m),
+                modulus: Some(m),
+            },
+            None => ModN {
+                value: plain(self.value, other.value),
+                modulus: None,
+            },
+        }
+    }
+}
+
+impl Add for ModN {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.combine(
+            other,
+            |a, b, m| ((u128::from(a) + u128::from(b)) % u128::from(m)) as u64,
+            |a, b| a + b,
+        )
+    }
+}
+
+impl Sub for ModN {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.combine(
+            other,
+            |a, b, m| if a >= b { a - b } else { m - (b - a) },
+            |a, b| a - b,
+        )
+    }
+}
+
+impl Mul for ModN {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.combine(other, mul_mod, |a, b| a * b)
+    }
+}
+
+/// Multiplication by the inverse, panics if `other` isn't invertible.
+impl Div for ModN {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        self.combine(
+            other,
+            |a, b, m| {
+                let inverse = mod_inverse(b.into(), m.into())
+                    .unwrap_or_else(|| panic!("{b} has no inverse modulo {m}"));
+                mul_mod(a, inverse as u64, m)
+            },
+            |a, b| a / b,
+        )
+    }
+}
+
+/// Remainder of the represented value as a plain integer, it's only
+/// meaningful when `other` divides the modulus.
+impl Rem for ModN {
+    type Output = Self;
+
+    fn rem(self, other: Self) -> Self {
+        if let Some(m) = self.common_modulus(other) {
+            debug_assert!(
+                m.is_multiple_of(other.value),
+                "{} doesn't divide {m}",
+                other.value
+            );
+        }
+        ModN {
+            value: self.value % other.value,
+            modulus: None,
+        }
+    }
+}
+
+impl From<u64> for ModN {
+    fn from(n: u64) -> Self {
+        ModN {
+            value: n,
+            modulus: None,
+        }
+    }
+}
+
+impl FromStr for ModN {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Ok(s.parse::<u64>()?.into()) }
+}
+
+impl fmt::Display for ModN {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
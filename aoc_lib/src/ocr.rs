@@ -0,0 +1,58 @@
+use crate::structs::{
+    grid::{Grid, GridView},
+    position::Position,
+};
+
+pub const LETTER_WIDTH: usize = 4;
+pub const LETTER_HEIGHT: usize = 6;
+/// Horizontal distance between the start of two consecutive letters
+const LETTER_STEP: usize = LETTER_WIDTH + 1;
+
+/// The block letters the puzzles draw, lit pixels are `#`
+const FONT: &[(char, [&str; LETTER_HEIGHT])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads the text of a screen with a row of block letters, each 4 pixels
+/// wide and separated by a blank column. `None` if the screen isn't 6 pixels
+/// tall or any of the letters isn't known.
+pub fn recognize(screen: &Grid<bool>) -> Option<String> {
+    if screen.rows() != LETTER_HEIGHT {
+        return None;
+    }
+    (0..(screen.columns() + 1) / LETTER_STEP)
+        .map(|i| {
+            let top_left = Position::new(i * LETTER_STEP, 0);
+            recognize_letter(screen.view(top_left, LETTER_HEIGHT, LETTER_WIDTH))
+        })
+        .collect()
+}
+
+/// Letter drawn in a 4×6 window, if it's one of the known ones
+pub fn recognize_letter(glyph: GridView<'_, bool>) -> Option<char> {
+    FONT.iter()
+        .find(|(_, rows)| {
+            rows.iter().enumerate().all(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .all(|(x, c)| glyph[(x, y)] == (c == '#'))
+            })
+        })
+        .map(|&(letter, _)| letter)
+}
@@ -0,0 +1,305 @@
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseJsonError {
+    #[error("unexpected end of input")]
+    UnexpectedEnd,
+    #[error("unexpected {found:?} at position {position}")]
+    UnexpectedChar { found: char, position: usize },
+    #[error("invalid number '{0}'")]
+    InvalidNumber(String),
+    #[error("invalid escape sequence at position {0}")]
+    InvalidEscape(usize),
+}
+
+/// Any JSON value. Objects keep their keys in the order they were written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Value of `key` if this is an object that has it
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => {
+                fields.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Json {
+    type Err = ParseJsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = JsonParser { s, pos: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(value),
+            Some(_) => Err(parser.unexpected()),
+        }
+    }
+}
+
+/// Recursive descent over the input, `pos` is a byte offset that always sits
+/// on a char boundary
+struct JsonParser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn peek(&self) -> Option<char> { self.s[self.pos..].chars().next() }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn unexpected(&self) -> ParseJsonError {
+        match self.peek() {
+            Some(found) => ParseJsonError::UnexpectedChar {
+                found,
+                position: self.pos,
+            },
+            None => ParseJsonError::UnexpectedEnd,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseJsonError> {
+        if self.peek() != Some(c) {
+            return Err(self.unexpected());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn keyword(
+        &mut self,
+        word: &str,
+        value: Json,
+    ) -> Result<Json, ParseJsonError> {
+        for c in word.chars() {
+            self.expect(c)?;
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Json, ParseJsonError> {
+        self.skip_whitespace();
+        match self.peek().ok_or(ParseJsonError::UnexpectedEnd)? {
+            'n' => self.keyword("null", Json::Null),
+            't' => self.keyword("true", Json::Bool(true)),
+            'f' => self.keyword("false", Json::Bool(false)),
+            '"' => Ok(Json::String(self.string()?)),
+            '[' => self.array(),
+            '{' => self.object(),
+            '-' | '0'..='9' => self.number(),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// Comma separated items between `open` and `close`
+    fn sequence<T>(
+        &mut self,
+        open: char,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParseJsonError>,
+    ) -> Result<Vec<T>, ParseJsonError> {
+        self.expect(open)?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    return Ok(items);
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, ParseJsonError> {
+        Ok(Json::Array(self.sequence('[', ']', Self::value)?))
+    }
+
+    fn object(&mut self) -> Result<Json, ParseJsonError> {
+        let fields = self.sequence('{', '}', |parser| {
+            parser.skip_whitespace();
+            let key = parser.string()?;
+            parser.skip_whitespace();
+            parser.expect(':')?;
+            Ok((key, parser.value()?))
+        })?;
+        Ok(Json::Object(fields))
+    }
+
+    fn number(&mut self) -> Result<Json, ParseJsonError> {
+        let start = self.pos;
+        let rest = &self.s[start..];
+        let len = rest
+            .find(|c: char| {
+                !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E')
+            })
+            .unwrap_or(rest.len());
+        self.pos += len;
+        let text = &rest[..len];
+
+        // Rust accepts more than JSON does ("1.", ".5", "01"...)
+        let digits = text.strip_prefix('-').unwrap_or(text);
+        let int_part = digits.split(['.', 'e', 'E']).next().unwrap();
+        let valid = !int_part.is_empty()
+            && (int_part == "0" || !int_part.starts_with('0'))
+            && !digits.contains(".e")
+            && !digits.contains(".E")
+            && !digits.ends_with('.');
+        match text.parse() {
+            Ok(n) if valid && f64::is_finite(n) => Ok(Json::Number(n)),
+            _ => Err(ParseJsonError::InvalidNumber(text.to_string())),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseJsonError> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            let start = self.pos;
+            match self.bump().ok_or(ParseJsonError::UnexpectedEnd)? {
+                '"' => return Ok(string),
+                '\\' => string.push(self.escape(start)?),
+                c if c < ' ' => {
+                    self.pos = start;
+                    return Err(self.unexpected());
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    /// The character after a `\` that started at `start`
+    fn escape(&mut self, start: usize) -> Result<char, ParseJsonError> {
+        let invalid = || ParseJsonError::InvalidEscape(start);
+        let c = match self.bump().ok_or(ParseJsonError::UnexpectedEnd)? {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let high = self.hex_code().ok_or_else(invalid)?;
+                if !(0xD800..0xDC00).contains(&high) {
+                    return char::from_u32(high).ok_or_else(invalid);
+                }
+                // Characters outside the BMP come as a UTF-16 surrogate pair
+                if self.bump() != Some('\\') || self.bump() != Some('u') {
+                    return Err(invalid());
+                }
+                let low = self.hex_code().ok_or_else(invalid)?;
+                if !(0xDC00..0xE000).contains(&low) {
+                    return Err(invalid());
+                }
+                let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                return char::from_u32(code).ok_or_else(invalid);
+            }
+            _ => return Err(invalid()),
+        };
+        Ok(c)
+    }
+
+    /// The 4 hex digits of a `\u` escape
+    fn hex_code(&mut self) -> Option<u32> {
+        let digits = self.s.get(self.pos..self.pos + 4)?;
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let code = u32::from_str_radix(digits, 16).ok()?;
+        self.pos += 4;
+        Some(code)
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Compact canonical form: no whitespace, integral numbers without a
+/// fractional part and only the escapes strings need
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{b}"),
+            // JSON has no representation for them
+            Json::Number(n) if !n.is_finite() => write!(f, "null"),
+            Json::Number(n) => write!(f, "{n}"),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+pub mod json;
+
 const DIGIT_CHARS: [char; 10] =
     ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
 
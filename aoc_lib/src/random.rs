@@ -0,0 +1,34 @@
+//! Reproducible pseudo-random numbers, to make up test data.
+
+use std::ops::Range;
+
+/// Tiny xorshift generator. The same seed always gives the same numbers,
+/// which is all the tests need, but it's no good for anything else.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Panics if `seed` is 0, xorshift would only ever return 0
+    pub fn new(seed: u64) -> Rng {
+        assert!(seed != 0, "Xorshift needs a seed other than 0");
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Number in `0..n`, panics if `n` is 0
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Random (possibly empty) range within `0..len`
+    pub fn range(&mut self, len: usize) -> Range<usize> {
+        let start = self.below(len + 1);
+        start..start + self.below(len - start + 1)
+    }
+}
@@ -0,0 +1,51 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// Breadth first search from `start` over the states given by `neighbors`.
+/// Returns the path from `start` to the first state that satisfies `is_goal`
+/// (both included), or `None` if no goal is reachable.
+pub fn bfs<S, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Vec<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    // The start's parent is itself
+    let mut parents: HashMap<S, S> = HashMap::from([(start.clone(), start)]);
+    let mut q: VecDeque<S> = parents.keys().cloned().collect();
+
+    while let Some(current) = q.pop_front() {
+        if is_goal(&current) {
+            return Some(reconstruct_path(&parents, current));
+        }
+
+        for neighbor in neighbors(&current) {
+            if !parents.contains_key(&neighbor) {
+                parents.insert(neighbor.clone(), current.clone());
+                q.push_back(neighbor);
+            }
+        }
+    }
+
+    None
+}
+
+fn reconstruct_path<S: Clone + Eq + Hash>(
+    parents: &HashMap<S, S>,
+    end: S,
+) -> Vec<S> {
+    let mut current = end;
+    let mut total_path = vec![current.clone()];
+    while parents[&current] != current {
+        current = parents[&current].clone();
+        total_path.push(current.clone());
+    }
+
+    total_path.reverse();
+    total_path
+}
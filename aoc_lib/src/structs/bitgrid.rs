@@ -0,0 +1,409 @@
+use super::position::Position;
+
+type Word = u64;
+const WORD_BITS: usize = Word::BITS as usize;
+
+/// 2D grid of booleans packed into bits. Each row starts on a new `u64` word
+/// (bit `x % 64` of word `x / 64` is column `x`), so whole rows can be
+/// shifted and combined with a few word operations.
+///
+/// Bits past the last column are always kept at 0.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<Word>,
+    rows: usize,
+    columns: usize,
+    words_per_row: usize,
+}
+
+impl BitGrid {
+    /// Grid with every bit unset
+    pub fn new(rows: usize, columns: usize) -> BitGrid {
+        let words_per_row = columns.div_ceil(WORD_BITS);
+        BitGrid {
+            words: vec![0; rows * words_per_row],
+            rows,
+            columns,
+            words_per_row,
+        }
+    }
+
+    #[inline]
+    pub fn rows(&self) -> usize { self.rows }
+
+    #[inline]
+    pub fn columns(&self) -> usize { self.columns }
+
+    /// `None` if the position is out of bounds
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> Option<bool> {
+        if x >= self.columns {
+            return None;
+        }
+        // Rows past the last one are caught by the bounds check of the words
+        let word = self.words.get(y * self.words_per_row + x / WORD_BITS)?;
+        Some((word >> (x % WORD_BITS)) & 1 != 0)
+    }
+
+    /// Whether the bit at `pos` is set, out of bounds positions aren't.
+    #[inline]
+    pub fn contains(&self, pos: Position<usize>) -> bool {
+        self.get(pos.x, pos.y).unwrap_or(false)
+    }
+
+    /// Panics if the position is out of bounds
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        let (i, mask) = self.locate_or_panic(x, y);
+        if value {
+            self.words[i] |= mask;
+        } else {
+            self.words[i] &= !mask;
+        }
+    }
+
+    /// Sets the bit at `pos`, returns whether it wasn't already set. Panics if
+    /// the position is out of bounds.
+    #[inline]
+    pub fn insert(&mut self, pos: Position<usize>) -> bool {
+        let (i, mask) = self.locate_or_panic(pos.x, pos.y);
+        let was_set = self.words[i] & mask != 0;
+        self.words[i] |= mask;
+        !was_set
+    }
+
+    /// Unsets the bit at `pos`, returns whether it was set. Panics if the
+    /// position is out of bounds.
+    #[inline]
+    pub fn remove(&mut self, pos: Position<usize>) -> bool {
+        let (i, mask) = self.locate_or_panic(pos.x, pos.y);
+        let was_set = self.words[i] & mask != 0;
+        self.words[i] &= !mask;
+        was_set
+    }
+
+    pub fn fill(&mut self, value: bool) {
+        self.words.fill(if value { Word::MAX } else { 0 });
+        if value {
+            for y in 0..self.rows {
+                self.clear_padding(y);
+            }
+        }
+    }
+
+    /// Amount of set bits in the whole grid
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Amount of set bits in row `y`
+    pub fn row_count_ones(&self, y: usize) -> usize {
+        self.row(y).iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Positions of every set bit, row by row
+    pub fn iter_ones(&self) -> impl Iterator<Item = Position<usize>> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let y = i / self.words_per_row;
+            let x0 = (i % self.words_per_row) * WORD_BITS;
+            let mut bits = word;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let x = x0 + bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(Position::new(x, y))
+            })
+        })
+    }
+
+    /// Column of the first set bit of row `y` that's at column `x` or after it
+    #[inline]
+    pub fn next_one_in_row(&self, y: usize, x: usize) -> Option<usize> {
+        if x >= self.columns {
+            return None;
+        }
+        let row = self.row(y);
+        let mut i = x / WORD_BITS;
+        let mut word = row[i] & (Word::MAX << (x % WORD_BITS));
+        while word == 0 {
+            i += 1;
+            word = *row.get(i)?;
+        }
+        Some(i * WORD_BITS + word.trailing_zeros() as usize)
+    }
+
+    /// Packed words of row `y`, column `x` is bit `x % 64` of word `x / 64`.
+    #[inline]
+    pub fn row(&self, y: usize) -> &[Word] {
+        let start = y * self.words_per_row;
+        &self.words[start..start + self.words_per_row]
+    }
+
+    /// Row `y` ORed with `bits` (the packed words of another row of the same
+    /// width, like the ones returned by [`BitGrid::row`]).
+    pub fn or_row(&mut self, y: usize, bits: &[Word]) {
+        self.zip_row(y, bits, |w, b| w | b);
+    }
+
+    /// Row `y` ANDed with `bits`
+    pub fn and_row(&mut self, y: usize, bits: &[Word]) {
+        self.zip_row(y, bits, |w, b| w & b);
+    }
+
+    /// Clears from row `y` every bit set in `bits`
+    pub fn and_not_row(&mut self, y: usize, bits: &[Word]) {
+        self.zip_row(y, bits, |w, b| w & !b);
+    }
+
+    /// Moves every bit of row `y` from column `x` to `x - n`, the ones that
+    /// end up before the first column are lost.
+    pub fn shift_row_left(&mut self, y: usize, n: usize) {
+        let start = y * self.words_per_row;
+        let row = &mut self.words[start..start + self.words_per_row];
+        let (word_shift, bit_shift) = (n / WORD_BITS, n % WORD_BITS);
+
+        for i in 0..row.len() {
+            let lo = row.get(i + word_shift).copied().unwrap_or(0);
+            let hi = row.get(i + word_shift + 1).copied().unwrap_or(0);
+            row[i] = if bit_shift == 0 {
+                lo
+            } else {
+                (lo >> bit_shift) | (hi << (WORD_BITS - bit_shift))
+            };
+        }
+    }
+
+    /// Moves every bit of row `y` from column `x` to `x + n`, the ones that
+    /// end up past the last column are lost.
+    pub fn shift_row_right(&mut self, y: usize, n: usize) {
+        let start = y * self.words_per_row;
+        let row = &mut self.words[start..start + self.words_per_row];
+        let (word_shift, bit_shift) = (n / WORD_BITS, n % WORD_BITS);
+
+        for i in (0..row.len()).rev() {
+            let word = |j: Option<usize>| j.map_or(0, |j| row[j]);
+            let hi = word(i.checked_sub(word_shift));
+            let lo = word(i.checked_sub(word_shift + 1));
+            row[i] = if bit_shift == 0 {
+                hi
+            } else {
+                (hi << bit_shift) | (lo >> (WORD_BITS - bit_shift))
+            };
+        }
+        self.clear_padding(y);
+    }
+
+    fn zip_row(
+        &mut self,
+        y: usize,
+        bits: &[Word],
+        f: impl Fn(Word, Word) -> Word,
+    ) {
+        assert_eq!(
+            bits.len(),
+            self.words_per_row,
+            "Row of {} words given, but the grid rows are {} words long",
+            bits.len(),
+            self.words_per_row
+        );
+        let start = y * self.words_per_row;
+        let row = &mut self.words[start..start + self.words_per_row];
+        for (w, &b) in row.iter_mut().zip(bits) {
+            *w = f(*w, b);
+        }
+        self.clear_padding(y);
+    }
+
+    /// Unsets the bits of row `y` past the last column
+    fn clear_padding(&mut self, y: usize) {
+        let used = self.columns % WORD_BITS;
+        if used != 0 {
+            let last = (y + 1) * self.words_per_row - 1;
+            self.words[last] &= (1 << used) - 1;
+        }
+    }
+
+    #[inline]
+    fn locate(&self, x: usize, y: usize) -> Option<(usize, Word)> {
+        (x < self.columns && y < self.rows).then(|| {
+            (y * self.words_per_row + x / WORD_BITS, 1 << (x % WORD_BITS))
+        })
+    }
+
+    #[inline]
+    fn locate_or_panic(&self, x: usize, y: usize) -> (usize, Word) {
+        self.locate(x, y).unwrap_or_else(|| {
+            panic!(
+                "Position {} out of bounds, the dimensions are {:?}",
+                Position::new(x, y),
+                (self.columns, self.rows)
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Rng;
+
+    /// Plain grid of booleans to check [`BitGrid`] against
+    #[derive(Debug)]
+    struct Model {
+        rows: Vec<Vec<bool>>,
+        columns: usize,
+    }
+
+    impl Model {
+        fn assert_matches(&self, grid: &BitGrid, step: &str) {
+            assert_eq!(grid.rows(), self.rows.len(), "Rows after {step}");
+            assert_eq!(grid.columns(), self.columns, "Columns after {step}");
+            for (y, row) in self.rows.iter().enumerate() {
+                for (x, &value) in row.iter().enumerate() {
+                    assert_eq!(grid.get(x, y), Some(value), "After {step}");
+                }
+                assert_eq!(grid.get(self.columns, y), None, "After {step}");
+
+                let used = self.columns % WORD_BITS;
+                if used != 0 {
+                    let last = grid.row(y).last().unwrap();
+                    assert_eq!(last >> used, 0, "Padding of {y} after {step}");
+                }
+                let ones = row.iter().filter(|&&b| b).count();
+                assert_eq!(grid.row_count_ones(y), ones, "After {step}");
+                let mut next = None;
+                for x in (0..=self.columns).rev() {
+                    if x < self.columns && row[x] {
+                        next = Some(x);
+                    }
+                    assert_eq!(
+                        grid.next_one_in_row(y, x),
+                        next,
+                        "next_one_in_row({y}, {x}) after {step}"
+                    );
+                }
+            }
+
+            let ones: Vec<_> = (0..self.rows.len())
+                .flat_map(|y| (0..self.columns).map(move |x| (x, y)))
+                .filter(|&(x, y)| self.rows[y][x])
+                .map(|(x, y)| Position::new(x, y))
+                .collect();
+            assert_eq!(grid.iter_ones().collect::<Vec<_>>(), ones, "{step}");
+            assert_eq!(grid.count_ones(), ones.len(), "After {step}");
+        }
+    }
+
+    /// Applies the same random operations to a [`BitGrid`] and a [`Model`],
+    /// comparing them after every step. The widths are picked around the
+    /// word boundaries, so that shifts move bits from one word to another.
+    fn bit_operations(seed: u64, steps: usize) {
+        let mut rng = Rng::new(seed);
+        let columns = [0, 1, 5, 63, 64, 65, 128, 150][rng.below(8)];
+        let rows = 1 + rng.below(4);
+        let mut grid = BitGrid::new(rows, columns);
+        let mut model = Model {
+            rows: vec![vec![false; columns]; rows],
+            columns,
+        };
+
+        for _ in 0..steps {
+            let y = rng.below(rows);
+            let step = match rng.below(8) {
+                0 | 1 if columns > 0 => {
+                    let x = rng.below(columns);
+                    let value = rng.below(3) != 0;
+                    grid.set(x, y, value);
+                    model.rows[y][x] = value;
+                    format!("set({x}, {y}, {value})")
+                }
+                2 if columns > 0 => {
+                    let pos = Position::new(rng.below(columns), y);
+                    let inserted = !model.rows[y][pos.x];
+                    assert_eq!(grid.insert(pos), inserted, "insert({pos})");
+                    model.rows[y][pos.x] = true;
+                    format!("insert({pos})")
+                }
+                3 if columns > 0 => {
+                    let pos = Position::new(rng.below(columns), y);
+                    let removed = model.rows[y][pos.x];
+                    assert_eq!(grid.remove(pos), removed, "remove({pos})");
+                    model.rows[y][pos.x] = false;
+                    format!("remove({pos})")
+                }
+                4 => {
+                    let n = rng.below(columns + 70);
+                    grid.shift_row_left(y, n);
+                    let row = &mut model.rows[y];
+                    *row = (0..columns)
+                        .map(|x| x + n < columns && row[x + n])
+                        .collect();
+                    format!("shift_row_left({y}, {n})")
+                }
+                5 => {
+                    let n = rng.below(columns + 70);
+                    grid.shift_row_right(y, n);
+                    let row = &mut model.rows[y];
+                    *row = (0..columns).map(|x| x >= n && row[x - n]).collect();
+                    format!("shift_row_right({y}, {n})")
+                }
+                6 => {
+                    let other = rng.below(rows);
+                    let bits = grid.row(other).to_vec();
+                    let op = rng.below(3);
+                    match op {
+                        0 => grid.or_row(y, &bits),
+                        1 => grid.and_row(y, &bits),
+                        _ => grid.and_not_row(y, &bits),
+                    }
+                    let (name, f): (_, fn(bool, bool) -> bool) = match op {
+                        0 => ("or_row", |a, b| a | b),
+                        1 => ("and_row", |a, b| a & b),
+                        _ => ("and_not_row", |a, b| a & !b),
+                    };
+                    let bits = model.rows[other].clone();
+                    for (a, b) in model.rows[y].iter_mut().zip(bits) {
+                        *a = f(*a, b);
+                    }
+                    format!("{name}({y}, row {other})")
+                }
+                7 if rng.below(8) == 0 => {
+                    let value = rng.below(2) == 0;
+                    grid.fill(value);
+                    for row in &mut model.rows {
+                        row.fill(value);
+                    }
+                    format!("fill({value})")
+                }
+                _ => continue,
+            };
+            model.assert_matches(&grid, &step);
+        }
+    }
+
+    #[test]
+    fn bit_operations_match_model() {
+        for seed in 1..=200 {
+            bit_operations(seed * 0x9E37_79B9, 200);
+        }
+    }
+
+    /// Setting the padding by hand and clearing it leaves the columns alone
+    #[test]
+    fn clear_padding_keeps_columns() {
+        for columns in [1, 63, 64, 65, 100, 128, 129] {
+            let mut grid = BitGrid::new(3, columns);
+            grid.words.fill(Word::MAX);
+            grid.clear_padding(1);
+
+            assert_eq!(grid.row_count_ones(1), columns);
+            let used = columns % WORD_BITS;
+            for y in [0, 2] {
+                let padding = if used == 0 { 0 } else { WORD_BITS - used };
+                assert_eq!(grid.row_count_ones(y), columns + padding);
+            }
+        }
+    }
+}
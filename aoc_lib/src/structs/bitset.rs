@@ -0,0 +1,249 @@
+use std::{
+    fmt,
+    iter::FusedIterator,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Sub},
+};
+
+type Word = u128;
+const WORD_BITS: usize = Word::BITS as usize;
+
+/// Fixed size set of small integers, stored as `N` 128 bit words so it can
+/// hold the values `0..N * 128`. Inserting or querying a value outside that
+/// range panics.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitSet<const N: usize> {
+    words: [Word; N],
+}
+
+impl<const N: usize> BitSet<N> {
+    /// Amount of values that fit in the set
+    pub const CAPACITY: usize = N * WORD_BITS;
+
+    pub fn new() -> BitSet<N> { BitSet { words: [0; N] } }
+
+    /// Adds `value` to the set, returns whether it wasn't already there.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, mask) = Self::locate(value);
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] |= mask;
+        !was_set
+    }
+
+    /// Removes `value` from the set, returns whether it was there.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let (word, mask) = Self::locate(value);
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] &= !mask;
+        was_set
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        let (word, mask) = Self::locate(value);
+        self.words[word] & mask != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool { self.words.iter().all(|&w| w == 0) }
+
+    pub fn clear(&mut self) { self.words = [0; N]; }
+
+    /// Smallest value in the set
+    pub fn first(&self) -> Option<usize> {
+        self.words.iter().enumerate().find_map(|(i, &w)| {
+            (w != 0).then(|| i * WORD_BITS + w.trailing_zeros() as usize)
+        })
+    }
+
+    /// Values in the set in ascending order
+    pub fn iter(&self) -> Iter<'_, N> {
+        Iter {
+            set: self,
+            word: 0,
+            current: self.words.first().copied().unwrap_or(0),
+        }
+    }
+
+    fn locate(value: usize) -> (usize, Word) {
+        assert!(
+            value < Self::CAPACITY,
+            "{value} doesn't fit in a BitSet of capacity {}",
+            Self::CAPACITY
+        );
+        (value / WORD_BITS, 1 << (value % WORD_BITS))
+    }
+
+    fn zip_with(self, other: Self, f: impl Fn(Word, Word) -> Word) -> Self {
+        let mut words = self.words;
+        for (w, o) in words.iter_mut().zip(other.words) {
+            *w = f(*w, o);
+        }
+        BitSet { words }
+    }
+}
+
+impl<const N: usize> Default for BitSet<N> {
+    fn default() -> Self { BitSet::new() }
+}
+
+impl<const N: usize> fmt::Debug for BitSet<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const N: usize> FromIterator<usize> for BitSet<N> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<const N: usize> Extend<usize> for BitSet<N> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+/// Intersection
+impl<const N: usize> BitAnd for BitSet<N> {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self { self.zip_with(other, |a, b| a & b) }
+}
+
+impl<const N: usize> BitAndAssign for BitSet<N> {
+    fn bitand_assign(&mut self, other: Self) { *self = *self & other; }
+}
+
+/// Union
+impl<const N: usize> BitOr for BitSet<N> {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self { self.zip_with(other, |a, b| a | b) }
+}
+
+impl<const N: usize> BitOrAssign for BitSet<N> {
+    fn bitor_assign(&mut self, other: Self) { *self = *self | other; }
+}
+
+/// Symmetric difference
+impl<const N: usize> BitXor for BitSet<N> {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self { self.zip_with(other, |a, b| a ^ b) }
+}
+
+/// Difference
+impl<const N: usize> Sub for BitSet<N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self { self.zip_with(other, |a, b| a & !b) }
+}
+
+/// Iterator returned by [`BitSet::iter`]
+pub struct Iter<'a, const N: usize> {
+    set: &'a BitSet<N>,
+    word: usize,
+    /// Bits of the current word that haven't been yielded yet
+    current: Word,
+}
+
+impl<const N: usize> Iterator for Iter<'_, N> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.word += 1;
+            self.current = *self.set.words.get(self.word)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.word * WORD_BITS + bit)
+    }
+}
+
+impl<const N: usize> FusedIterator for Iter<'_, N> {}
+
+impl<'a, const N: usize> IntoIterator for &'a BitSet<N> {
+    type Item = usize;
+    type IntoIter = Iter<'a, N>;
+
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Rng;
+    use std::collections::BTreeSet;
+
+    type Set = BitSet<3>;
+
+    /// Random set of values near the word boundaries, so that they spread
+    /// over every word and the iterator has to move across them
+    fn random_set(rng: &mut Rng) -> (Set, BTreeSet<usize>) {
+        let mut set = Set::new();
+        let mut model = BTreeSet::new();
+        for _ in 0..rng.below(12) {
+            let value =
+                (rng.below(3) * WORD_BITS + rng.below(8)).saturating_sub(4);
+            assert_eq!(set.insert(value), model.insert(value), "{value}");
+        }
+        (set, model)
+    }
+
+    fn assert_matches(set: &Set, model: &BTreeSet<usize>, step: &str) {
+        let values: Vec<_> = model.iter().copied().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), values, "After {step}");
+        assert_eq!(set.len(), model.len(), "After {step}");
+        assert_eq!(set.is_empty(), model.is_empty(), "After {step}");
+        assert_eq!(set.first(), model.first().copied(), "After {step}");
+    }
+
+    #[test]
+    fn set_operations_match_model() {
+        let mut rng = Rng::new(0x2022_0303);
+        for _ in 0..2_000 {
+            let (mut set, mut model) = random_set(&mut rng);
+            assert_matches(&set, &model, "inserting");
+            let (other, other_model) = random_set(&mut rng);
+
+            let union = model.union(&other_model).copied().collect();
+            assert_matches(&(set | other), &union, "union");
+            let both = model.intersection(&other_model).copied().collect();
+            assert_matches(&(set & other), &both, "intersection");
+            let either =
+                model.symmetric_difference(&other_model).copied().collect();
+            assert_matches(&(set ^ other), &either, "symmetric difference");
+            let diff = model.difference(&other_model).copied().collect();
+            assert_matches(&(set - other), &diff, "difference");
+
+            for value in other_model {
+                assert_eq!(set.remove(value), model.remove(&value), "{value}");
+                assert!(!set.contains(value), "{value}");
+            }
+            assert_matches(&set, &model, "removing");
+        }
+    }
+
+    #[test]
+    fn iter_across_words() {
+        let set: Set = [0, 127, 128, 255, 383].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0, 127, 128, 255, 383]);
+
+        // Empty words at the start and in the middle are skipped
+        let set: Set = [200, 300].into_iter().collect();
+        let mut iter = set.iter();
+        assert_eq!(iter.next(), Some(200));
+        assert_eq!(iter.next(), Some(300));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(Set::new().iter().next(), None);
+    }
+}
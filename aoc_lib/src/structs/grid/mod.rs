@@ -0,0 +1,1072 @@
+use colored::Colorize;
+use std::{
+    collections::HashMap,
+    fmt,
+    iter::{repeat_n, Skip, StepBy},
+    ops::{Bound, Index, IndexMut, RangeBounds},
+    slice::{Chunks, ChunksMut, Iter, IterMut},
+    str::FromStr,
+};
+use thiserror::Error;
+
+pub use colored::Color;
+pub use regions::{Components, Region};
+
+use super::position::Position;
+
+mod regions;
+
+/// 2D rectangular grid structure
+
+#[derive(Debug, Clone)]
+pub struct Grid<T> {
+    elements: Vec<T>,
+    rows: usize,
+    columns: usize,
+}
+
+#[derive(Error, Debug)]
+#[error("vec given was of size {0} which isn't wholly divisble by the width given {1} ({0}/{1}={2:.1})", .vec_length, .width_given, (*.vec_length as f64) / (*.width_given as f64))]
+pub struct WrongDimensionsError {
+    vec_length: usize,
+    width_given: usize,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseGridError {
+    #[error("row {row} is {found} tiles long but the first one is {expected}")]
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("unexpected character '{c}' for a tile at ({x}, {y})")]
+    InvalidTile { c: char, x: usize, y: usize },
+}
+
+fn xy2i(columns: usize, x: usize, y: usize) -> usize { y * columns + x }
+
+/// Direction to move along a grid, up is towards row 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+/// Which tiles count as adjacent when walking the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// Orthogonal neighbors only
+    Four,
+    /// Orthogonal and diagonal neighbors
+    Eight,
+}
+
+impl Connectivity {
+    /// `(dx, dy)` from a tile to each of its neighbors, orthogonal ones first
+    pub fn offsets(self) -> &'static [(isize, isize)] {
+        const OFFSETS: [(isize, isize); 8] = [
+            (0, -1),
+            (0, 1),
+            (-1, 0),
+            (1, 0),
+            (-1, -1),
+            (1, -1),
+            (-1, 1),
+            (1, 1),
+        ];
+        match self {
+            Connectivity::Four => &OFFSETS[..4],
+            Connectivity::Eight => &OFFSETS,
+        }
+    }
+}
+
+/// Iterator over the elements of a column, top to bottom
+pub type Column<'a, T> = StepBy<Skip<Iter<'a, T>>>;
+
+/// Iterator over the mutable elements of a column, top to bottom
+pub type ColumnMut<'a, T> = StepBy<Skip<IterMut<'a, T>>>;
+
+impl<T> Grid<T> {
+    pub fn from_vec(
+        vec: Vec<T>,
+        columns: usize,
+    ) -> Result<Grid<T>, WrongDimensionsError> {
+        if (vec.len() % columns) == 0 {
+            Ok(Grid {
+                rows: vec.len() / columns,
+                elements: vec,
+                columns,
+            })
+        } else {
+            Err(WrongDimensionsError {
+                width_given: columns,
+                vec_length: vec.len(),
+            })
+        }
+    }
+
+    pub fn rows(&self) -> usize { self.rows }
+
+    pub fn columns(&self) -> usize { self.columns }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.elements.get(xy2i(self.columns, x, y))
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.elements.get_mut(xy2i(self.columns, x, y))
+    }
+
+    pub fn parse_grid_with<'a, I>(
+        input: &'a str,
+        map_fn: impl Fn(&'a str) -> I,
+    ) -> Grid<T>
+    where
+        I: Iterator<Item = T>,
+    {
+        let lines = input.lines();
+        let columns = lines.clone().next().unwrap().chars().count();
+        let rows = lines.clone().count();
+
+        let mut elements: Vec<T> = Vec::with_capacity(rows * columns);
+
+        map_fn(input).for_each(|n| elements.push(n));
+
+        Grid {
+            elements,
+            rows,
+            columns,
+        }
+    }
+
+    pub fn iter_all(&self) -> Iter<'_, T> { self.elements.iter() }
+    pub fn iter_mut_all(&mut self) -> IterMut<'_, T> {
+        self.elements.iter_mut()
+    }
+
+    pub fn iter_rows(&self) -> Chunks<'_, T> {
+        self.elements.chunks(self.columns)
+    }
+
+    pub fn iter_mut_rows(&mut self) -> ChunksMut<'_, T> {
+        self.elements.chunks_mut(self.columns)
+    }
+
+    /// Elements of column `x` from top to bottom
+    pub fn iter_column(&self, x: usize) -> Column<'_, T> {
+        assert!(x < self.columns, "Column {x} out of bounds");
+        self.elements.iter().skip(x).step_by(self.columns)
+    }
+
+    pub fn iter_mut_column(&mut self, x: usize) -> ColumnMut<'_, T> {
+        assert!(x < self.columns, "Column {x} out of bounds");
+        self.elements.iter_mut().skip(x).step_by(self.columns)
+    }
+
+    /// Every column, from left to right
+    pub fn iter_columns(&self) -> impl Iterator<Item = Column<'_, T>> {
+        (0..self.columns).map(|x| self.iter_column(x))
+    }
+
+    /// Elements from the one next to `(x, y)` (not included) in direction
+    /// `dir` until the edge of the grid.
+    pub fn ray(&self, x: usize, y: usize, dir: Direction) -> Ray<'_, T> {
+        assert!(
+            x < self.columns && y < self.rows,
+            "Ray start {} out of bounds, the dimensions are {:?}",
+            Position::new(x, y),
+            (self.columns, self.rows)
+        );
+        let remaining = match dir {
+            Direction::Up => y,
+            Direction::Down => self.rows - 1 - y,
+            Direction::Left => x,
+            Direction::Right => self.columns - 1 - x,
+        };
+        Ray {
+            grid: self,
+            pos: Position::new(x, y),
+            dir,
+            remaining,
+        }
+    }
+
+    /// Positions adjacent to `pos` that are inside the grid
+    pub fn neighbors(
+        &self,
+        pos: Position<usize>,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = Position<usize>> {
+        let (columns, rows) = (self.columns, self.rows);
+        connectivity.offsets().iter().filter_map(move |&(dx, dy)| {
+            let x = pos.x.checked_add_signed(dx).filter(|&x| x < columns)?;
+            let y = pos.y.checked_add_signed(dy).filter(|&y| y < rows)?;
+            Some(Position::new(x, y))
+        })
+    }
+
+    /// Borrowed rectangular window of the grid, with `top_left` as its origin.
+    /// Panics if it doesn't fit inside the grid.
+    pub fn view(
+        &self,
+        top_left: Position<usize>,
+        rows: usize,
+        columns: usize,
+    ) -> GridView<'_, T> {
+        self.check_window(top_left, rows, columns);
+        GridView {
+            grid: self,
+            origin: top_left,
+            rows,
+            columns,
+        }
+    }
+
+    /// Like [`Grid::view`] but the elements can be modified
+    pub fn view_mut(
+        &mut self,
+        top_left: Position<usize>,
+        rows: usize,
+        columns: usize,
+    ) -> GridViewMut<'_, T> {
+        self.check_window(top_left, rows, columns);
+        GridViewMut {
+            grid: self,
+            origin: top_left,
+            rows,
+            columns,
+        }
+    }
+
+    fn check_window(
+        &self,
+        top_left: Position<usize>,
+        rows: usize,
+        cols: usize,
+    ) {
+        if top_left.x + cols > self.columns || top_left.y + rows > self.rows {
+            panic!(
+                "Window of {:?} at {top_left} doesn't fit in a grid of {:?}",
+                (cols, rows),
+                (self.columns, self.rows)
+            );
+        }
+    }
+
+    /// Panics if the range goes past the last row
+    pub fn remove_rows<R: RangeBounds<usize>>(&mut self, range: R) {
+        let (start, end) = bounds(range, self.rows);
+
+        self.elements
+            .drain(start * self.columns..end * self.columns);
+        self.rows -= end - start;
+    }
+
+    /// The performance of removing columns is much worse than that of removing
+    /// rows (since columns aren't contiguous in memory every row has to be
+    /// moved). Consider reorganizing your data such that you can manage only
+    /// calling [`remove_rows`]. Panics if the range goes past the last column.
+    ///
+    /// [`remove_rows`]: Grid::remove_rows
+    pub fn remove_cols<R: RangeBounds<usize>>(&mut self, range: R) {
+        let (start, end) = bounds(range, self.columns);
+        if start == end {
+            return;
+        }
+
+        let columns = self.columns;
+        let mut i = 0;
+        self.elements.retain(|_| {
+            let x = i % columns;
+            i += 1;
+            !(start..end).contains(&x)
+        });
+
+        self.columns -= end - start;
+    }
+
+    /// Inserts `row` before row `y` (or after the last one if `y` is the
+    /// amount of rows). Panics if its length isn't the amount of columns,
+    /// unless the grid has no rows, then it sets the amount of columns.
+    pub fn insert_row(&mut self, y: usize, row: impl IntoIterator<Item = T>) {
+        assert!(y <= self.rows, "Row {y} out of bounds");
+        let row: Vec<T> = row.into_iter().collect();
+        if self.rows == 0 {
+            self.columns = row.len();
+        }
+        assert_eq!(
+            row.len(),
+            self.columns,
+            "Row of length {} inserted in a grid of {} columns",
+            row.len(),
+            self.columns
+        );
+
+        let i = y * self.columns;
+        self.elements.splice(i..i, row);
+        self.rows += 1;
+    }
+
+    pub fn append_row(&mut self, row: impl IntoIterator<Item = T>) {
+        self.insert_row(self.rows, row);
+    }
+
+    /// Inserts `column` before column `x` (or after the last one if `x` is the
+    /// amount of columns). Panics if its length isn't the amount of rows,
+    /// unless the grid has no columns, then it sets the amount of rows.
+    pub fn insert_column(
+        &mut self,
+        x: usize,
+        column: impl IntoIterator<Item = T>,
+    ) {
+        assert!(x <= self.columns, "Column {x} out of bounds");
+        let column: Vec<T> = column.into_iter().collect();
+        if self.columns == 0 {
+            self.rows = column.len();
+        }
+        assert_eq!(
+            column.len(),
+            self.rows,
+            "Column of length {} inserted in a grid of {} rows",
+            column.len(),
+            self.rows
+        );
+
+        let old = std::mem::take(&mut self.elements);
+        self.elements.reserve_exact(old.len() + self.rows);
+        let mut old = old.into_iter();
+        for value in column {
+            self.elements.extend(old.by_ref().take(x));
+            self.elements.push(value);
+            self.elements.extend(old.by_ref().take(self.columns - x));
+        }
+        self.columns += 1;
+    }
+
+    pub fn append_column(&mut self, column: impl IntoIterator<Item = T>) {
+        self.insert_column(self.columns, column);
+    }
+
+    /// Keeps only the rectangle between the corners `min` and `max` (both
+    /// included), like the bounding box of some positions. Panics if it
+    /// doesn't fit in the grid.
+    pub fn crop(&mut self, min: Position<usize>, max: Position<usize>) {
+        assert!(
+            min.x <= max.x && min.y <= max.y,
+            "Bounding box from {min} to {max} is empty"
+        );
+        self.check_window(min, max.y - min.y + 1, max.x - min.x + 1);
+
+        self.remove_rows(max.y + 1..);
+        self.remove_rows(..min.y);
+        self.remove_cols(max.x + 1..);
+        self.remove_cols(..min.x);
+    }
+}
+
+/// Converts `range` into the start (included) and end (excluded) indices, for
+/// something of length `len`.
+fn bounds<R: RangeBounds<usize>>(range: R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Unbounded => 0,
+        Bound::Included(&i) => i,
+        Bound::Excluded(&i) => i + 1,
+    };
+    let end = match range.end_bound() {
+        Bound::Unbounded => len,
+        Bound::Included(&i) => i + 1,
+        Bound::Excluded(&i) => i,
+    };
+    assert!(
+        start <= end && end <= len,
+        "Range {start}..{end} out of bounds for length {len}"
+    );
+
+    (start, end)
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn fill(&mut self, value: T) { self.elements.fill(value); }
+
+    /// Changes the dimensions keeping the elements at the top left corner,
+    /// new positions get `value`.
+    pub fn resize(&mut self, rows: usize, columns: usize, value: T) {
+        if columns < self.columns {
+            self.remove_cols(columns..);
+        } else if columns > self.columns {
+            self.widen(0, columns - self.columns, &value);
+        }
+
+        self.elements.resize(rows * columns, value);
+        self.rows = rows;
+    }
+
+    /// Surrounds the grid with a border `border` tiles wide filled with
+    /// `value`.
+    pub fn pad(&mut self, border: usize, value: T) {
+        self.widen(border, border, &value);
+
+        let edge = border * self.columns;
+        self.elements.splice(0..0, repeat_n(value.clone(), edge));
+        self.elements.extend(repeat_n(value, edge));
+        self.rows += 2 * border;
+    }
+
+    /// Adds `left` and `right` columns with `value` on each side
+    fn widen(&mut self, left: usize, right: usize, value: &T) {
+        let columns = left + self.columns + right;
+        let mut old = std::mem::take(&mut self.elements).into_iter();
+        self.elements.reserve_exact(self.rows * columns);
+        for _ in 0..self.rows {
+            self.elements.extend(repeat_n(value.clone(), left));
+            self.elements.extend(old.by_ref().take(self.columns));
+            self.elements.extend(repeat_n(value.clone(), right));
+        }
+        self.columns = columns;
+    }
+
+    /// Rows become columns, the element at `(x, y)` moves to `(y, x)`
+    pub fn transpose(&self) -> Grid<T> {
+        Grid::from_fn(self.columns, self.rows, |x, y| self[(y, x)].clone())
+    }
+
+    /// Rotated a quarter turn clockwise
+    pub fn rotate_90(&self) -> Grid<T> {
+        Grid::from_fn(self.columns, self.rows, |x, y| {
+            self[(y, self.rows - 1 - x)].clone()
+        })
+    }
+
+    pub fn rotate_180(&self) -> Grid<T> {
+        Grid::from_fn(self.rows, self.columns, |x, y| {
+            self[(self.columns - 1 - x, self.rows - 1 - y)].clone()
+        })
+    }
+
+    /// Rotated a quarter turn counterclockwise
+    pub fn rotate_270(&self) -> Grid<T> {
+        Grid::from_fn(self.columns, self.rows, |x, y| {
+            self[(self.columns - 1 - y, x)].clone()
+        })
+    }
+
+    /// Mirrored left to right
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        Grid::from_fn(self.rows, self.columns, |x, y| {
+            self[(self.columns - 1 - x, y)].clone()
+        })
+    }
+
+    /// Mirrored upside down
+    pub fn flip_vertical(&self) -> Grid<T> {
+        Grid::from_fn(self.rows, self.columns, |x, y| {
+            self[(x, self.rows - 1 - y)].clone()
+        })
+    }
+
+    pub fn with_val(val: T, rows: usize, columns: usize) -> Grid<T> {
+        Grid {
+            elements: vec![val; rows * columns],
+            columns,
+            rows,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Grid where the element at `(x, y)` is `f(x, y)`
+    pub fn from_fn(
+        rows: usize,
+        columns: usize,
+        mut f: impl FnMut(usize, usize) -> T,
+    ) -> Grid<T> {
+        let mut elements = Vec::with_capacity(rows * columns);
+        for y in 0..rows {
+            for x in 0..columns {
+                elements.push(f(x, y));
+            }
+        }
+        Grid {
+            elements,
+            rows,
+            columns,
+        }
+    }
+}
+
+impl<T: Default> Grid<T> {
+    pub fn new(rows: usize, columns: usize) -> Grid<T> {
+        let mut elements = Vec::new();
+        elements.resize_with(rows * columns, Default::default);
+        Grid {
+            elements,
+            columns,
+            rows,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coords: (usize, usize)) -> &Self::Output {
+        &self.elements[xy2i(self.columns, coords.0, coords.1)]
+    }
+}
+
+impl<T> Index<Position<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Position<usize>) -> &Self::Output {
+        &self.elements[xy2i(self.columns, pos.x, pos.y)]
+    }
+}
+
+impl<T> Index<usize> for Grid<T> {
+    type Output = [T];
+
+    fn index(&self, row: usize) -> &Self::Output {
+        let row_start = row * self.columns;
+        &self.elements[row_start..row_start + self.columns]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, coords: (usize, usize)) -> &mut Self::Output {
+        &mut self.elements[xy2i(self.columns, coords.0, coords.1)]
+    }
+}
+
+impl<T> IndexMut<Position<usize>> for Grid<T> {
+    fn index_mut(&mut self, pos: Position<usize>) -> &mut Self::Output {
+        &mut self.elements[xy2i(self.columns, pos.x, pos.y)]
+    }
+}
+
+impl<T> IndexMut<usize> for Grid<T> {
+    fn index_mut(&mut self, row: usize) -> &mut Self::Output {
+        let row_start = row * self.columns;
+        &mut self.elements[row_start..row_start + self.columns]
+    }
+}
+
+/// Element of a grid that's written as a single character, so the grid can be
+/// parsed from (with [`FromStr`]) and rendered to (with [`fmt::Display`]) the
+/// usual puzzle maps.
+pub trait GridTile: Sized {
+    /// `None` if `c` doesn't represent any tile
+    fn from_char(c: char) -> Option<Self>;
+
+    fn to_char(&self) -> char;
+}
+
+impl GridTile for char {
+    fn from_char(c: char) -> Option<Self> { Some(c) }
+
+    fn to_char(&self) -> char { *self }
+}
+
+/// `#` for `true` and `.` for `false`
+impl GridTile for bool {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        if *self {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
+
+/// A single decimal digit
+impl GridTile for u8 {
+    fn from_char(c: char) -> Option<Self> {
+        c.to_digit(10).map(|d| d as u8)
+    }
+
+    fn to_char(&self) -> char {
+        char::from_digit(*self as u32, 10).unwrap_or('?')
+    }
+}
+
+/// One row per line, accepts both `\n` and `\r\n` line endings
+impl<T: GridTile> FromStr for Grid<T> {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut elements = Vec::with_capacity(s.len());
+        let mut columns = None;
+        let mut rows = 0;
+
+        for (y, line) in s.lines().enumerate() {
+            let row_start = elements.len();
+            for (x, c) in line.chars().enumerate() {
+                let tile = T::from_char(c)
+                    .ok_or(ParseGridError::InvalidTile { c, x, y })?;
+                elements.push(tile);
+            }
+
+            let found = elements.len() - row_start;
+            let expected = *columns.get_or_insert(found);
+            if found != expected {
+                return Err(ParseGridError::RaggedRow {
+                    row: y,
+                    expected,
+                    found,
+                });
+            }
+            rows += 1;
+        }
+
+        Ok(Grid {
+            elements,
+            rows,
+            columns: columns.unwrap_or(0),
+        })
+    }
+}
+
+impl<T: GridTile> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.overlay().fmt(f)
+    }
+}
+
+impl<T: GridTile> Grid<T> {
+    /// Renderer of the grid that can highlight positions in color, like a
+    /// path through it.
+    pub fn overlay(&self) -> Overlay<'_, T> {
+        Overlay {
+            grid: self,
+            highlights: HashMap::new(),
+        }
+    }
+}
+
+/// Grid renderer created with [`Grid::overlay`], it's shown through its
+/// [`fmt::Display`] implementation.
+pub struct Overlay<'a, T> {
+    grid: &'a Grid<T>,
+    highlights: HashMap<Position<usize>, Color>,
+}
+
+impl<T> Overlay<'_, T> {
+    /// Shows the tiles at `positions` in bold `color`. If a position is
+    /// highlighted more than once the last color is used.
+    pub fn highlight(
+        mut self,
+        positions: impl IntoIterator<Item = Position<usize>>,
+        color: Color,
+    ) -> Self {
+        self.highlights
+            .extend(positions.into_iter().map(|pos| (pos, color)));
+        self
+    }
+}
+
+impl<T: GridTile> fmt::Display for Overlay<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.grid.columns == 0 {
+            return Ok(());
+        }
+        for (y, row) in self.grid.iter_rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for (x, tile) in row.iter().enumerate() {
+                let c = tile.to_char();
+                match self.highlights.get(&Position::new(x, y)) {
+                    Some(&color) => {
+                        write!(f, "{}", c.to_string().color(color).bold())?
+                    }
+                    None => write!(f, "{c}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Iterator returned by [`Grid::ray`]
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    pos: Position<usize>,
+    dir: Direction,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        match self.dir {
+            Direction::Up => self.pos.y -= 1,
+            Direction::Down => self.pos.y += 1,
+            Direction::Left => self.pos.x -= 1,
+            Direction::Right => self.pos.x += 1,
+        }
+        Some(&self.grid[self.pos])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Ray<'_, T> {}
+
+/// Rectangular window of a [`Grid`], created with [`Grid::view`]. Positions
+/// are relative to the top left corner of the window.
+#[derive(Debug)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Position<usize>,
+    rows: usize,
+    columns: usize,
+}
+
+// Not derived since that would require `T: Clone`
+impl<T> Clone for GridView<'_, T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T> Copy for GridView<'_, T> {}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn rows(&self) -> usize { self.rows }
+
+    pub fn columns(&self) -> usize { self.columns }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a T> {
+        if x >= self.columns || y >= self.rows {
+            return None;
+        }
+        self.grid.get(self.origin.x + x, self.origin.y + y)
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let (x, y) = (self.origin.x, self.origin.y);
+        let columns = self.columns;
+        self.grid.elements[y * self.grid.columns..]
+            .chunks(self.grid.columns)
+            .take(self.rows)
+            .map(move |row| &row[x..x + columns])
+    }
+
+    pub fn iter_all(&self) -> impl Iterator<Item = &'a T> {
+        self.iter_rows().flatten()
+    }
+}
+
+impl<T: Clone> GridView<'_, T> {
+    /// Copies the window into its own grid
+    pub fn to_grid(&self) -> Grid<T> {
+        Grid {
+            elements: self.iter_all().cloned().collect(),
+            rows: self.rows,
+            columns: self.columns,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for GridView<'_, T> {
+    type Output = T;
+
+    fn index(&self, coords: (usize, usize)) -> &Self::Output {
+        self.get(coords.0, coords.1).unwrap_or_else(|| {
+            panic!("Position {coords:?} out of bounds of the grid view")
+        })
+    }
+}
+
+impl<T> Index<Position<usize>> for GridView<'_, T> {
+    type Output = T;
+
+    fn index(&self, pos: Position<usize>) -> &Self::Output {
+        &self[(pos.x, pos.y)]
+    }
+}
+
+/// Mutable rectangular window of a [`Grid`], created with [`Grid::view_mut`].
+/// Positions are relative to the top left corner of the window.
+#[derive(Debug)]
+pub struct GridViewMut<'a, T> {
+    grid: &'a mut Grid<T>,
+    origin: Position<usize>,
+    rows: usize,
+    columns: usize,
+}
+
+impl<T> GridViewMut<'_, T> {
+    pub fn rows(&self) -> usize { self.rows }
+
+    pub fn columns(&self) -> usize { self.columns }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x >= self.columns || y >= self.rows {
+            return None;
+        }
+        self.grid.get(self.origin.x + x, self.origin.y + y)
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x >= self.columns || y >= self.rows {
+            return None;
+        }
+        self.grid.get_mut(self.origin.x + x, self.origin.y + y)
+    }
+
+    /// Immutable view of the same window
+    pub fn as_view(&self) -> GridView<'_, T> {
+        GridView {
+            grid: self.grid,
+            origin: self.origin,
+            rows: self.rows,
+            columns: self.columns,
+        }
+    }
+
+    pub fn iter_mut_rows(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let (x, y) = (self.origin.x, self.origin.y);
+        let columns = self.columns;
+        let grid_columns = self.grid.columns;
+        self.grid.elements[y * grid_columns..]
+            .chunks_mut(grid_columns)
+            .take(self.rows)
+            .map(move |row| &mut row[x..x + columns])
+    }
+
+    pub fn iter_mut_all(&mut self) -> impl Iterator<Item = &mut T> {
+        self.iter_mut_rows().flatten()
+    }
+}
+
+impl<T: Clone> GridViewMut<'_, T> {
+    pub fn fill(&mut self, value: T) {
+        for row in self.iter_mut_rows() {
+            row.fill(value.clone());
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for GridViewMut<'_, T> {
+    type Output = T;
+
+    fn index(&self, coords: (usize, usize)) -> &Self::Output {
+        self.get(coords.0, coords.1).unwrap_or_else(|| {
+            panic!("Position {coords:?} out of bounds of the grid view")
+        })
+    }
+}
+
+impl<T> Index<Position<usize>> for GridViewMut<'_, T> {
+    type Output = T;
+
+    fn index(&self, pos: Position<usize>) -> &Self::Output {
+        &self[(pos.x, pos.y)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for GridViewMut<'_, T> {
+    fn index_mut(&mut self, coords: (usize, usize)) -> &mut Self::Output {
+        self.get_mut(coords.0, coords.1).unwrap_or_else(|| {
+            panic!("Position {coords:?} out of bounds of the grid view")
+        })
+    }
+}
+
+impl<T> IndexMut<Position<usize>> for GridViewMut<'_, T> {
+    fn index_mut(&mut self, pos: Position<usize>) -> &mut Self::Output {
+        &mut self[(pos.x, pos.y)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Rng;
+    use std::ops::Range;
+
+    /// The obvious implementation of a grid to check [`Grid`] against. The
+    /// amount of columns is kept apart since there may be no rows.
+    #[derive(Debug)]
+    struct Model {
+        rows: Vec<Vec<u32>>,
+        columns: usize,
+    }
+
+    impl Model {
+        fn assert_matches(&self, grid: &Grid<u32>, step: &str) {
+            assert_eq!(grid.rows(), self.rows.len(), "Rows after {step}");
+            assert_eq!(grid.columns(), self.columns, "Columns after {step}");
+            for (y, row) in self.rows.iter().enumerate() {
+                for (x, value) in row.iter().enumerate() {
+                    assert_eq!(grid.get(x, y), Some(value), "After {step}");
+                }
+            }
+        }
+    }
+
+    /// Applies the same random resizing operations to a [`Grid`] and a
+    /// [`Model`], comparing them after every step
+    fn resize_operations(seed: u64, steps: usize) {
+        let mut rng = Rng::new(seed);
+        let mut next_value = 0;
+        let mut value = || {
+            next_value += 1;
+            next_value
+        };
+
+        let mut grid = Grid::from_fn(3, 4, |_, _| value());
+        let mut model = Model {
+            rows: grid.iter_rows().map(|r| r.to_vec()).collect(),
+            columns: 4,
+        };
+
+        for _ in 0..steps {
+            let (rows, columns) = (model.rows.len(), model.columns);
+            let step = match rng.below(8) {
+                0 => {
+                    let Range { start, end } = rng.range(rows);
+                    grid.remove_rows(start..end);
+                    model.rows.drain(start..end);
+                    format!("remove_rows({start}..{end})")
+                }
+                1 => {
+                    let Range { start, end } = rng.range(columns);
+                    grid.remove_cols(start..end);
+                    for row in &mut model.rows {
+                        row.drain(start..end);
+                    }
+                    model.columns -= end - start;
+                    format!("remove_cols({start}..{end})")
+                }
+                2 if rows < 8 => {
+                    let y = rng.below(rows + 1);
+                    let len = if rows == 0 { rng.below(5) } else { columns };
+                    let row: Vec<u32> = (0..len).map(|_| value()).collect();
+                    grid.insert_row(y, row.clone());
+                    model.rows.insert(y, row);
+                    model.columns = len;
+                    format!("insert_row({y})")
+                }
+                3 if columns < 8 => {
+                    let x = rng.below(columns + 1);
+                    let len = if columns == 0 { rng.below(5) } else { rows };
+                    let column: Vec<u32> = (0..len).map(|_| value()).collect();
+                    grid.insert_column(x, column.clone());
+                    if columns == 0 {
+                        model.rows = column.iter().map(|&v| vec![v]).collect();
+                    } else {
+                        for (row, v) in model.rows.iter_mut().zip(column) {
+                            row.insert(x, v);
+                        }
+                    }
+                    model.columns += 1;
+                    format!("insert_column({x})")
+                }
+                4 | 5 => {
+                    let (new_rows, new_columns) = (rng.below(8), rng.below(8));
+                    let fill = value();
+                    grid.resize(new_rows, new_columns, fill);
+                    for row in &mut model.rows {
+                        row.resize(new_columns, fill);
+                    }
+                    model.rows.resize(new_rows, vec![fill; new_columns]);
+                    model.columns = new_columns;
+                    format!("resize({new_rows}, {new_columns})")
+                }
+                6 if rows < 6 && columns < 6 => {
+                    let border = rng.below(3);
+                    let fill = value();
+                    grid.pad(border, fill);
+                    let columns = columns + 2 * border;
+                    for row in &mut model.rows {
+                        row.splice(0..0, vec![fill; border]);
+                        row.extend(vec![fill; border]);
+                    }
+                    let edge = vec![vec![fill; columns]; border];
+                    model.rows.splice(0..0, edge.clone());
+                    model.rows.extend(edge);
+                    model.columns = columns;
+                    format!("pad({border})")
+                }
+                7 if rows > 0 && columns > 0 => {
+                    let (x1, x2) = (rng.below(columns), rng.below(columns));
+                    let (y1, y2) = (rng.below(rows), rng.below(rows));
+                    let min = Position::new(x1.min(x2), y1.min(y2));
+                    let max = Position::new(x1.max(x2), y1.max(y2));
+                    grid.crop(min, max);
+                    model.rows = model.rows[min.y..=max.y]
+                        .iter()
+                        .map(|row| row[min.x..=max.x].to_vec())
+                        .collect();
+                    model.columns = max.x - min.x + 1;
+                    format!("crop({min}, {max})")
+                }
+                _ => continue,
+            };
+            model.assert_matches(&grid, &step);
+        }
+    }
+
+    #[test]
+    fn resizing_matches_model() {
+        for seed in 1..=200 {
+            resize_operations(seed * 0x9E37_79B9, 200);
+        }
+    }
+
+    /// Highlighted tiles are bold and colored, the last color given wins
+    #[test]
+    fn overlay_highlights_path() {
+        let grid: Grid<char> = "abc\ndef".parse().unwrap();
+        let path = [(0, 0), (1, 0), (1, 1)].map(|(x, y)| Position::new(x, y));
+
+        colored::control::set_override(true);
+        let text = grid
+            .overlay()
+            .highlight(path, Color::Red)
+            .highlight([Position::new(1, 1)], Color::Blue)
+            .to_string();
+        colored::control::unset_override();
+
+        let red = |c| format!("\x1b[1;31m{c}\x1b[0m");
+        let expected =
+            format!("{}{}c\nd\x1b[1;34me\x1b[0mf", red('a'), red('b'));
+        assert_eq!(text, expected);
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+
+use super::{Connectivity, Grid};
+use crate::structs::position::Position;
+
+/// Label of the tiles that haven't been reached yet
+const UNLABELED: usize = usize::MAX;
+
+/// Connected set of tiles of a grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    positions: Vec<Position<usize>>,
+    perimeter: usize,
+    min: Position<usize>,
+    max: Position<usize>,
+}
+
+impl Region {
+    /// Tiles of the region, in the order they were reached from the first one
+    pub fn positions(&self) -> &[Position<usize>] { &self.positions }
+
+    /// Amount of tiles
+    pub fn size(&self) -> usize { self.positions.len() }
+
+    /// Amount of tile sides between the region and the tiles outside of it,
+    /// including the ones on the edge of the grid. Only orthogonal sides count,
+    /// even if the region was built with [`Connectivity::Eight`].
+    pub fn perimeter(&self) -> usize { self.perimeter }
+
+    /// Top left and bottom right corners (inclusive) of the smallest rectangle
+    /// that holds the region
+    pub fn bounding_box(&self) -> (Position<usize>, Position<usize>) {
+        (self.min, self.max)
+    }
+}
+
+/// Every tile of a grid split into connected regions
+#[derive(Debug, Clone)]
+pub struct Components {
+    /// Index in `regions` of the region of each tile
+    pub labels: Grid<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl Components {
+    /// Region the tile at `pos` belongs to
+    pub fn region_at(&self, pos: Position<usize>) -> &Region {
+        &self.regions[self.labels[pos]]
+    }
+}
+
+impl<T> Grid<T> {
+    /// Region of the tiles reachable from `seed` moving only through tiles
+    /// where `passable` holds, or `None` if the seed itself isn't passable.
+    pub fn flood_fill(
+        &self,
+        seed: Position<usize>,
+        connectivity: Connectivity,
+        mut passable: impl FnMut(&T) -> bool,
+    ) -> Option<Region> {
+        if !passable(&self[seed]) {
+            return None;
+        }
+        let mut labels = self.unlabeled();
+        let accept = |_: &T, to: &T| passable(to);
+        Some(self.grow_region(seed, 0, &mut labels, connectivity, accept))
+    }
+
+    /// Splits the grid into regions of adjacent tiles that are equal
+    pub fn components(&self, connectivity: Connectivity) -> Components
+    where
+        T: PartialEq,
+    {
+        self.components_by(connectivity, |a, b| a == b)
+    }
+
+    /// Splits the grid into regions, where two adjacent tiles are in the same
+    /// one if `same_region` holds for them. It's checked both ways, so the
+    /// regions don't depend on which tile is reached first even if it isn't
+    /// symmetric. Tiles are still grouped transitively though: with a relation
+    /// like "heights differ by 1 at most", tiles of very different heights can
+    /// end up together. The regions are numbered in the order their first
+    /// tile appears, row by row.
+    pub fn components_by(
+        &self,
+        connectivity: Connectivity,
+        mut same_region: impl FnMut(&T, &T) -> bool,
+    ) -> Components {
+        let mut labels = self.unlabeled();
+        let mut regions = Vec::new();
+
+        for y in 0..self.rows {
+            for x in 0..self.columns {
+                let seed = Position::new(x, y);
+                if labels[seed] == UNLABELED {
+                    let label = regions.len();
+                    regions.push(self.grow_region(
+                        seed,
+                        label,
+                        &mut labels,
+                        connectivity,
+                        |a: &T, b: &T| same_region(a, b) && same_region(b, a),
+                    ));
+                }
+            }
+        }
+
+        Components { labels, regions }
+    }
+
+    fn unlabeled(&self) -> Grid<usize> {
+        Grid::from_fn(self.rows, self.columns, |_, _| UNLABELED)
+    }
+
+    /// Breadth first search from `seed`, stepping from one tile to an
+    /// unlabeled neighbor when `accept(from, to)` holds. Every tile reached is
+    /// marked with `label`.
+    fn grow_region(
+        &self,
+        seed: Position<usize>,
+        label: usize,
+        labels: &mut Grid<usize>,
+        connectivity: Connectivity,
+        mut accept: impl FnMut(&T, &T) -> bool,
+    ) -> Region {
+        let mut positions = vec![seed];
+        let mut queue = VecDeque::from([seed]);
+        labels[seed] = label;
+
+        while let Some(pos) = queue.pop_front() {
+            for next in self.neighbors(pos, connectivity) {
+                let reached = labels[next] != UNLABELED;
+                if !reached && accept(&self[pos], &self[next]) {
+                    labels[next] = label;
+                    positions.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let perimeter = positions
+            .iter()
+            .map(|&pos| {
+                let inside = self
+                    .neighbors(pos, Connectivity::Four)
+                    .filter(|&n| labels[n] == label)
+                    .count();
+                4 - inside
+            })
+            .sum();
+        let min = Position::new(
+            positions.iter().map(|p| p.x).min().unwrap(),
+            positions.iter().map(|p| p.y).min().unwrap(),
+        );
+        let max = Position::new(
+            positions.iter().map(|p| p.x).max().unwrap(),
+            positions.iter().map(|p| p.y).max().unwrap(),
+        );
+
+        Region {
+            positions,
+            perimeter,
+            min,
+            max,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ```text
+    /// aab
+    /// aba
+    /// baa
+    /// ```
+    fn grid() -> Grid<char> { "aab\naba\nbaa".parse().unwrap() }
+
+    fn positions(coords: &[(usize, usize)]) -> Vec<Position<usize>> {
+        coords.iter().map(|&(x, y)| Position::new(x, y)).collect()
+    }
+
+    #[test]
+    fn four_vs_eight() {
+        let grid = grid();
+
+        let four = grid.components(Connectivity::Four);
+        let sizes: Vec<_> = four.regions.iter().map(Region::size).collect();
+        assert_eq!(sizes, [3, 1, 1, 3, 1]);
+        let corner = &four.regions[0];
+        assert_eq!(corner.positions(), positions(&[(0, 0), (0, 1), (1, 0)]));
+        assert_eq!(corner.perimeter(), 8);
+        let corners = (Position::new(0, 0), Position::new(1, 1));
+        assert_eq!(corner.bounding_box(), corners);
+        assert_eq!(four.labels[Position::new(2, 2)], 3);
+
+        // Diagonals join every a, and every b
+        let eight = grid.components(Connectivity::Eight);
+        let sizes: Vec<_> = eight.regions.iter().map(Region::size).collect();
+        assert_eq!(sizes, [6, 3]);
+        let a = eight.region_at(Position::new(2, 2));
+        assert_eq!(a, &eight.regions[0]);
+        // Still only orthogonal sides
+        assert_eq!(a.perimeter(), 16);
+        let corners = (Position::new(0, 0), Position::new(2, 2));
+        assert_eq!(a.bounding_box(), corners);
+    }
+
+    #[test]
+    fn flood_fill() {
+        let grid = grid();
+        let is_a = |&c: &char| c == 'a';
+        let (center, corner) = (Position::new(1, 1), Position::new(0, 0));
+
+        assert_eq!(grid.flood_fill(center, Connectivity::Four, is_a), None);
+        let four = grid.flood_fill(corner, Connectivity::Four, is_a);
+        assert_eq!(four.map(|r| r.size()), Some(3));
+        let eight = grid.flood_fill(corner, Connectivity::Eight, is_a);
+        assert_eq!(eight.map(|r| r.size()), Some(6));
+
+        // Every side of the whole grid is on its edge
+        let all = grid.flood_fill(center, Connectivity::Four, |_| true);
+        assert_eq!(all.map(|r| (r.size(), r.perimeter())), Some((9, 12)));
+    }
+
+    /// Tiles are only joined when the relation holds both ways, whichever of
+    /// them comes first
+    #[test]
+    fn asymmetric_relation() {
+        for s in ["ab", "ba"] {
+            let grid: Grid<char> = s.parse().unwrap();
+            let components =
+                grid.components_by(Connectivity::Four, |a, b| a <= b);
+            assert_eq!(components.regions.len(), 2, "{s}");
+        }
+    }
+}
@@ -1,2 +1,5 @@
+pub mod bitgrid;
+pub mod bitset;
 pub mod grid;
 pub mod position;
+pub mod snafu;
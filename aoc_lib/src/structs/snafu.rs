@@ -0,0 +1,113 @@
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign},
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// SNAFU number: balanced base 5 where the digits are `=` (-2), `-` (-1), `0`,
+/// `1` and `2`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Snafu(i128);
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseSnafuError {
+    #[error("empty string isn't a SNAFU number")]
+    Empty,
+    #[error("invalid SNAFU digit '{0}' (expected one of = - 0 1 2)")]
+    InvalidDigit(char),
+    #[error("SNAFU number is too big to be represented")]
+    Overflow,
+}
+
+impl Snafu {
+    pub fn new(n: i128) -> Snafu { Snafu(n) }
+
+    pub fn value(self) -> i128 { self.0 }
+}
+
+impl FromStr for Snafu {
+    type Err = ParseSnafuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseSnafuError::Empty);
+        }
+
+        s.chars()
+            .try_fold(0i128, |n, c| {
+                let digit = match c {
+                    '=' => -2,
+                    '-' => -1,
+                    '0' => 0,
+                    '1' => 1,
+                    '2' => 2,
+                    _ => return Err(ParseSnafuError::InvalidDigit(c)),
+                };
+                n.checked_mul(5)
+                    .and_then(|n| n.checked_add(digit))
+                    .ok_or(ParseSnafuError::Overflow)
+            })
+            .map(Snafu)
+    }
+}
+
+impl fmt::Display for Snafu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0 {
+            return write!(f, "0");
+        }
+
+        // Digits are pulled out from the least significant one
+        let mut digits = Vec::new();
+        let mut n = self.0;
+        while n != 0 {
+            let (digit, carry) = match n.rem_euclid(5) {
+                0 => ('0', 0),
+                1 => ('1', 0),
+                2 => ('2', 0),
+                3 => ('=', 1),
+                _ => ('-', 1),
+            };
+            digits.push(digit);
+            n = n.div_euclid(5) + carry;
+        }
+
+        write!(f, "{}", digits.iter().rev().collect::<String>())
+    }
+}
+
+impl Add for Snafu {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output { Snafu(self.0 + other.0) }
+}
+
+impl AddAssign for Snafu {
+    fn add_assign(&mut self, other: Self) { self.0 += other.0; }
+}
+
+impl Sum for Snafu {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), Add::add)
+    }
+}
+
+impl From<i64> for Snafu {
+    fn from(n: i64) -> Snafu { Snafu(n.into()) }
+}
+
+impl From<i128> for Snafu {
+    fn from(n: i128) -> Snafu { Snafu(n) }
+}
+
+impl From<Snafu> for i128 {
+    fn from(n: Snafu) -> i128 { n.0 }
+}
+
+impl TryFrom<Snafu> for i64 {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(n: Snafu) -> Result<i64, Self::Error> { n.0.try_into() }
+}
@@ -1,5 +1,12 @@
 pub use seq_macro::seq;
-use std::fmt;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+};
 
 pub enum SolutionResult {
     Str(String),
@@ -19,40 +26,83 @@ impl fmt::Display for SolutionResult {
 
 pub type Solution = fn(&str) -> SolutionResult;
 
+/// Another implementation of one of the tasks of a day, `--bench` times it
+/// after the day's solutions to compare them.
+#[derive(Clone, Copy)]
+pub struct Alternative {
+    pub day: usize,
+    /// 1 or 2
+    pub task: usize,
+    pub name: &'static str,
+    pub solution: Solution,
+}
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Makes the solutions explain their work, see [`verbose`].
+pub fn set_verbose(verbose: bool) { VERBOSE.store(verbose, Ordering::Relaxed); }
+
+/// Whether `--verbose` was given. Solutions that can show how they got their
+/// answer (traces, drawings...) print it when it is.
+pub fn verbose() -> bool { VERBOSE.load(Ordering::Relaxed) }
+
+static FRAMES_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Makes the simulations that can be animated save their frames under `dir`,
+/// see [`frames_dir`]. Only the first call has any effect.
+pub fn set_frames_dir(dir: PathBuf) { FRAMES_DIR.get_or_init(|| dir); }
+
+/// Directory given with `--frames`, if any. Simulations write their frames to
+/// a subdirectory named after their day.
+pub fn frames_dir() -> Option<&'static Path> {
+    FRAMES_DIR.get().map(PathBuf::as_path)
+}
+
+/// Builds a table indexed by `day - 1` with the solutions of every day in the
+/// given ranges, days outside of them are left as `None`. Every day in the
+/// ranges must have a `dayN` module with `task1` and `task2`.
 #[macro_export]
 macro_rules! solutions {
-    ($max_day:expr) => {
-        seq!(N in 1..=$max_day {
-            [
-            #([
-                |data: _| day~N::task1(data),
-                |data: _| day~N::task2(data)
-            ],)*
-        ]
-            })
-    }
+    ($max_day:expr; $($from:literal..=$to:literal),+) => {{
+        let mut solutions: [Option<[Solution; 2]>; $max_day] = [None; $max_day];
+        $(
+            seq!(N in $from..=$to {
+                #(
+                    solutions[N - 1] = Some([
+                        day~N::task1 as Solution,
+                        day~N::task2 as Solution,
+                    ]);
+                )*
+            });
+        )+
+        solutions
+    }};
 }
 
+/// Same as [`solutions!`] but for the `input.txt` and `testinput.txt` files.
 #[macro_export]
 macro_rules! inputs {
-    ($max_day:expr) => {
-            seq!(N in 1..=$max_day {
-            [
-            #(
-                    [
-             include_str!(concat!("day", N, "/input.txt")),
-             include_str!(concat!("day", N, "/testinput.txt")),
-                    ]
-            ,)*
-        ]
-            })
-    }
+    ($max_day:expr; $($from:literal..=$to:literal),+) => {{
+        let mut inputs: [Option<[&str; 2]>; $max_day] = [None; $max_day];
+        $(
+            seq!(N in $from..=$to {
+                #(
+                    inputs[N - 1] = Some([
+                        include_str!(concat!("day", N, "/input.txt")),
+                        include_str!(concat!("day", N, "/testinput.txt")),
+                    ]);
+                )*
+            });
+        )+
+        inputs
+    }};
 }
 
 pub fn load_input(
-    inputs: &[[&'static str; 2]],
+    inputs: &[Option<[&'static str; 2]>],
     day: usize,
     load_test: bool,
 ) -> &'static str {
-    inputs[day - 1][load_test as usize]
+    inputs[day - 1].expect("Loaded input of a day without solution")
+        [load_test as usize]
 }
@@ -1,4 +1,4 @@
-use aoc_lib::tooling::SolutionResult;
+use aoc_lib::{iter::AocIterExt, tooling::SolutionResult};
 
 pub fn task1(input: &str) -> SolutionResult {
     // Tuple (current_sum, max_found)
@@ -21,22 +21,11 @@ pub fn task1(input: &str) -> SolutionResult {
 }
 
 pub fn task2(input: &str) -> SolutionResult {
-    // Tuple (current_sum, elves_calories_vec)
-    let mut sums: Vec<i32> = input
+    let top_3 = input
         .lines()
-        .fold((0, vec![0]), |mut tuple, line| {
-            if let Ok(i) = line.parse::<i32>() {
-                *(tuple.1.last_mut().unwrap()) += i;
-            } else {
-                tuple.0 = 0;
-                tuple.1.push(0);
-            }
-            tuple
-        })
-        .1;
+        .split_on(|line| line.is_empty())
+        .map(|elf| elf.iter().sum_by(|line| line.parse::<i32>().unwrap()))
+        .top_k(3);
 
-    sums.sort();
-    sums.reverse();
-
-    SolutionResult::Signed(sums[0] + sums[1] + sums[2])
+    SolutionResult::Signed(top_3.iter().sum())
 }
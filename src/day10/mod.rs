@@ -1,21 +1,64 @@
-use std::{fmt, str::FromStr, string::ParseError};
+use std::{collections::BTreeSet, fmt, str::FromStr, string::ParseError};
 
-use itertools::Either;
+use aoc_lib::{
+    ocr,
+    structs::grid::Grid,
+    tooling::{verbose, SolutionResult},
+};
 
-use aoc_lib::tooling::SolutionResult;
+const SCREEN_WIDTH: usize = 40;
+const SCREEN_HEIGHT: usize = 6;
 
-#[derive(Clone, Copy)]
-enum Instruction {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Opcode {
     Noop,
-    Addx(i32),
+    Addx,
+}
+
+struct OpcodeInfo {
+    opcode: Opcode,
+    name: &'static str,
+    takes_arg: bool,
+    /// Cycles it takes to finish executing
+    cycles: usize,
+}
+
+/// Every instruction the CPU understands
+const INSTRUCTION_SET: [OpcodeInfo; 2] = [
+    OpcodeInfo {
+        opcode: Opcode::Noop,
+        name: "noop",
+        takes_arg: false,
+        cycles: 1,
+    },
+    OpcodeInfo {
+        opcode: Opcode::Addx,
+        name: "addx",
+        takes_arg: true,
+        cycles: 2,
+    },
+];
+
+impl Opcode {
+    fn info(self) -> &'static OpcodeInfo {
+        INSTRUCTION_SET.iter().find(|i| i.opcode == self).unwrap()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Instruction {
+    opcode: Opcode,
+    arg: i32,
 }
 
 impl fmt::Display for Instruction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Instruction::Noop => write!(f, "noop"),
-            Instruction::Addx(add) => write!(f, "addx({add})"),
+        let info = self.opcode.info();
+        write!(f, "{}", info.name)?;
+        if info.takes_arg {
+            write!(f, " {}", self.arg)?;
         }
+        Ok(())
     }
 }
 
@@ -23,69 +66,303 @@ impl FromStr for Instruction {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match &s[0..4] {
-            "noop" => Instruction::Noop,
-            "addx" => Instruction::Addx(s[5..].parse().unwrap()),
-            _ => panic!("Wrong instruction format"),
+        let (name, arg) = s.split_once(' ').unwrap_or((s, ""));
+        let info = INSTRUCTION_SET
+            .iter()
+            .find(|i| i.name == name)
+            .unwrap_or_else(|| panic!("Unknown instruction '{s}'"));
+        let arg = if info.takes_arg { arg.parse().unwrap() } else { 0 };
+
+        Ok(Instruction {
+            opcode: info.opcode,
+            arg,
         })
     }
 }
 
-fn generate_cycles(
-    iter: impl Iterator<Item = Instruction>,
-) -> impl Iterator<Item = i32> {
-    let mut x_reg = 1;
+fn parse_program(input: &str) -> Vec<Instruction> {
+    input.lines().map(|l| l.parse().unwrap()).collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Registers {
+    x: i32,
+}
+
+impl Registers {
+    /// Effect of `inst` once it finishes executing
+    fn apply(&mut self, inst: Instruction) {
+        match inst.opcode {
+            Opcode::Noop => {}
+            Opcode::Addx => self.x += inst.arg,
+        }
+    }
+}
+
+/// State of the CPU during a cycle
+#[derive(Clone, Copy, Debug)]
+struct Cycle {
+    /// Starting from 1
+    number: usize,
+    registers: Registers,
+}
 
-    (1..=1).chain(
-        iter //.inspect(|inst| println!("{inst}"))
-            .flat_map(move |inst| match inst {
-                Instruction::Noop => Either::Left([x_reg].into_iter()),
-                Instruction::Addx(add) => {
-                    x_reg += add;
-                    Either::Right([x_reg - add, x_reg].into_iter())
-                }
-            }),
-    )
-    //.inspect(|x| println!("X: {x}"))
+/// Why [`Cpu::run`] returned
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stop {
+    Halted,
+    /// Paused at the start of that cycle, before it executes
+    Breakpoint(usize),
+}
+
+/// Called during every cycle, see [`Cpu::on_cycle`]
+type Hook<'a> = Box<dyn FnMut(&Cycle) + 'a>;
+
+struct Cpu<'a> {
+    program: Vec<Instruction>,
+    registers: Registers,
+    /// Index of the instruction being executed
+    pc: usize,
+    /// Cycles the current instruction has run for
+    progress: usize,
+    /// Cycles completed so far
+    cycle: usize,
+    breakpoints: BTreeSet<usize>,
+    /// Breakpoint [`Cpu::run`] last paused at, so resuming doesn't stop there
+    /// again
+    paused_at: Option<usize>,
+    hooks: Vec<Hook<'a>>,
+    trace: bool,
+    /// Lines traced since the last [`Cpu::take_trace`]
+    trace_lines: Vec<String>,
+}
+
+impl<'a> Cpu<'a> {
+    fn new(program: Vec<Instruction>) -> Cpu<'a> {
+        Cpu {
+            program,
+            registers: Registers { x: 1 },
+            pc: 0,
+            progress: 0,
+            cycle: 0,
+            breakpoints: BTreeSet::new(),
+            paused_at: None,
+            hooks: Vec::new(),
+            trace: false,
+            trace_lines: Vec::new(),
+        }
+    }
+
+    fn registers(&self) -> Registers { self.registers }
+
+    /// Describes what happens every cycle, like the example in the task. The
+    /// lines are kept until [`Cpu::take_trace`] is called.
+    fn set_trace(&mut self, trace: bool) { self.trace = trace; }
+
+    fn take_trace(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.trace_lines)
+    }
+
+    /// Makes [`Cpu::run`] pause at the start of cycle `cycle`
+    fn add_breakpoint(&mut self, cycle: usize) {
+        self.breakpoints.insert(cycle);
+    }
+
+    /// Calls `hook` during every cycle
+    fn on_cycle(&mut self, hook: impl FnMut(&Cycle) + 'a) {
+        self.hooks.push(Box::new(hook));
+    }
+
+    /// Executes a single cycle, `None` if the program already finished.
+    fn step(&mut self) -> Option<Cycle> {
+        let inst = *self.program.get(self.pc)?;
+        let cycle = Cycle {
+            number: self.cycle + 1,
+            registers: self.registers,
+        };
+        for hook in &mut self.hooks {
+            hook(&cycle);
+        }
+
+        let starting = self.progress == 0;
+        self.cycle += 1;
+        self.progress += 1;
+        let finished = self.progress == inst.opcode.info().cycles;
+        if finished {
+            self.registers.apply(inst);
+            self.pc += 1;
+            self.progress = 0;
+        }
+
+        if self.trace {
+            self.trace_cycle(inst, &cycle, starting, finished);
+        }
+
+        Some(cycle)
+    }
+
+    /// Runs until the program finishes or a breakpoint is reached. Calling it
+    /// again resumes from the breakpoint.
+    fn run(&mut self) -> Stop {
+        loop {
+            let next = self.cycle + 1;
+            let running = self.pc < self.program.len();
+            if running
+                && self.breakpoints.contains(&next)
+                && self.paused_at != Some(next)
+            {
+                self.paused_at = Some(next);
+                return Stop::Breakpoint(next);
+            }
+            if self.step().is_none() {
+                return Stop::Halted;
+            }
+        }
+    }
+
+    fn trace_cycle(
+        &mut self,
+        inst: Instruction,
+        cycle: &Cycle,
+        starting: bool,
+        finished: bool,
+    ) {
+        let nth = ordinal(cycle.number);
+        let x = cycle.registers.x;
+        let mut line = String::new();
+
+        if starting {
+            line += &format!(
+                "At the start of the {nth} cycle, the {inst} instruction \
+                 begins execution. "
+            );
+        }
+        // X only changes between cycles
+        let still = if cycle.number > 1 { "still " } else { "" };
+        line += &format!("During the {nth} cycle, X is {still}{x}.");
+        if finished {
+            let effect = match inst.opcode {
+                Opcode::Noop => "doing nothing".to_string(),
+                Opcode::Addx => format!("setting X to {}", self.registers.x),
+            };
+            line += &format!(
+                " After the {nth} cycle, the {inst} instruction finishes \
+                 execution, {effect}."
+            );
+        }
+
+        self.trace_lines.push(line);
+    }
+}
+
+impl Iterator for Cpu<'_> {
+    type Item = Cycle;
+
+    fn next(&mut self) -> Option<Cycle> { self.step() }
+}
+
+/// "first", "second"... up to "tenth", then "11th", "21st"...
+fn ordinal(n: usize) -> String {
+    const WORDS: [&str; 10] = [
+        "first", "second", "third", "fourth", "fifth", "sixth", "seventh",
+        "eighth", "ninth", "tenth",
+    ];
+    if let Some(word) = n.checked_sub(1).and_then(|i| WORDS.get(i)) {
+        return word.to_string();
+    }
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
 }
 
 pub fn task1(input: &str) -> SolutionResult {
-    let cycles = generate_cycles(input.lines().map(|l| l.parse().unwrap()));
+    let mut cpu = Cpu::new(parse_program(input));
+    cpu.set_trace(verbose());
+    for cycle in (20..=220).step_by(40) {
+        cpu.add_breakpoint(cycle);
+    }
 
-    let res = cycles
-        .enumerate()
-        .skip(19)
-        .step_by(40)
-        //.inspect(|(i, x)| println!("{}: X({x}) => {}", i + 1, (i + 1) as i32 * x))
-        .fold(0, |acc, (i, x)| acc + (i + 1) as i32 * x);
+    let mut res = 0;
+    while let Stop::Breakpoint(cycle) = cpu.run() {
+        let x = cpu.registers().x;
+        let strength = cycle as i32 * x;
+        if verbose() {
+            cpu.take_trace().iter().for_each(|l| println!("{l}"));
+            println!(
+                "During the {} cycle, register X has the value {x}, so the \
+                 signal strength is {cycle} * {x} = {strength}.",
+                ordinal(cycle)
+            );
+        }
+        res += strength;
+    }
+    cpu.take_trace().iter().for_each(|l| println!("{l}"));
 
     SolutionResult::Signed(res)
 }
 
-#[allow(dead_code)]
-fn b2c(b: bool) -> char {
-    match b {
-        true => '#',
-        false => '.',
-    }
+/// Pixels lit by the CRT while drawing the program's output
+fn draw_screen(input: &str) -> Grid<bool> {
+    let mut pixels = Vec::with_capacity(SCREEN_WIDTH * SCREEN_HEIGHT);
+    let mut cpu = Cpu::new(parse_program(input));
+    cpu.on_cycle(|c| {
+        let pos = ((c.number - 1) % SCREEN_WIDTH) as i32;
+        let x = c.registers.x;
+        pixels.push((x - 1..=x + 1).contains(&pos));
+    });
+    // Programs may keep running after the screen is drawn
+    cpu.add_breakpoint(SCREEN_WIDTH * SCREEN_HEIGHT + 1);
+    cpu.run();
+    drop(cpu);
+
+    Grid::from_vec(pixels, SCREEN_WIDTH).unwrap()
 }
 
 pub fn task2(input: &str) -> SolutionResult {
-    let cycles =
-        generate_cycles(input.lines().map(|l| l.parse().unwrap())).take(240);
+    let screen = draw_screen(input);
 
-    for (i, x) in cycles.enumerate() {
-        let pos = i % 40;
+    let res = ocr::recognize(&screen).unwrap_or_else(|| format!("\n{screen}"));
 
-        #[allow(unused_variables)]
-        let pixel = (x - 1..=x + 1).contains(&(pos as i32));
+    SolutionResult::Str(res)
+}
 
-        //print!("{}", b2c(pixel));
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if pos == 39 {
-            //print!("\n");
+    #[test]
+    fn breakpoints_from_the_first_cycle() {
+        let mut cpu = Cpu::new(parse_program("noop\naddx 3\naddx -5"));
+        for cycle in [1, 2, 4] {
+            cpu.add_breakpoint(cycle);
         }
+
+        assert_eq!(cpu.run(), Stop::Breakpoint(1));
+        assert_eq!(cpu.run(), Stop::Breakpoint(2));
+        assert_eq!(cpu.run(), Stop::Breakpoint(4));
+        assert_eq!(cpu.registers().x, 4);
+        assert_eq!(cpu.run(), Stop::Halted);
     }
 
-    SolutionResult::Str("Enable prints to see result".to_string())
+    /// The trace of the small example program matches the one in the task
+    #[test]
+    fn trace_matches_task() {
+        let task = include_str!("task.txt");
+        let expected: Vec<&str> = task
+            .lines()
+            .skip_while(|l| !l.contains("At the start of the first cycle"))
+            .take(5)
+            .map(str::trim)
+            .collect();
+
+        let mut cpu = Cpu::new(parse_program("noop\naddx 3\naddx -5"));
+        cpu.set_trace(true);
+        assert_eq!(cpu.run(), Stop::Halted);
+        assert_eq!(cpu.take_trace(), expected);
+    }
 }
@@ -1,39 +1,227 @@
 use std::{
     cmp::Ordering,
     collections::VecDeque,
-    fmt::Debug,
-    ops::{Add, Div, Mul, Range},
+    fmt::{self, Debug},
+    ops::{Add, Div, Mul, Rem},
     str::FromStr,
     string::ParseError,
 };
 
-use aoc_lib::tooling::SolutionResult;
+use thiserror::Error;
 
-type Num = u64;
+use aoc_lib::{
+    bigint::BigUint,
+    cycle::simulate_n,
+    expr::{Arithmetic, Expr, Operator},
+    iter::AocIterExt,
+    math::{lcm_all, ModN},
+    tooling::{verbose, SolutionResult},
+};
 
-#[derive(Debug, Clone)]
-enum Op<M: Mul + Clone, A: Add + Clone> {
-    Sum(A),
-    Mul(M),
-    Square,
+/// Signed, since an operation can subtract from the worry levels
+type Num = i64;
+type Worry = ModN;
+
+/// The only variable in a monkey's operation, the `old` worry level
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Old;
+
+impl fmt::Display for Old {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "old")
+    }
+}
+
+/// Worry level types the monkeys can run their tests on
+trait WorryLevel: Arithmetic + Rem<Output = Self> + Clone + Eq + Default {
+    /// Whether the actual worry levels are kept. Otherwise they can only be
+    /// tested for divisibility and never divided.
+    const EXACT: bool;
+
+    /// Ordering of the actual worry levels, `None` if the type doesn't keep
+    /// enough of them to know it.
+    fn compare(&self, other: &Self) -> Option<Ordering>;
+}
+
+impl WorryLevel for Num {
+    const EXACT: bool = true;
+
+    fn compare(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Only the remainders are kept, which is enough for divisibility only
+impl WorryLevel for Worry {
+    const EXACT: bool = false;
+
+    fn compare(&self, _other: &Self) -> Option<Ordering> { None }
+}
+
+/// The actual worry levels, without any limit on how big they get
+impl WorryLevel for BigUint {
+    const EXACT: bool = true;
+
+    fn compare(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+enum MonkeyError {
+    #[error("monkey {0} isn't listed in order")]
+    OutOfOrder(usize),
+    #[error("monkey {monkey} throws to monkey {to}, which doesn't exist")]
+    UnknownTarget { monkey: usize, to: usize },
+    #[error("monkey {0} throws items to itself")]
+    SelfThrow(usize),
+    #[error("monkey {0} can't divide worry levels kept as remainders")]
+    Division(usize),
+    #[error("monkey {monkey} can't test if remainders are {test}")]
+    Comparison { monkey: usize, test: String },
+}
+
+/// What a monkey checks to decide who to throw an item to
+#[derive(Debug, Clone, PartialEq)]
+enum Test<T> {
+    DivisibleBy(T),
+    GreaterThan(T),
+    LessThan(T),
+    EqualTo(T),
+}
+
+impl<T: WorryLevel> Test<T> {
+    fn check(&self, worry: &T) -> bool {
+        let compare = |n: &T| {
+            worry.compare(n).unwrap_or_else(|| {
+                panic!("Comparison tests need the full worry levels")
+            })
+        };
+        match self {
+            Test::DivisibleBy(n) => worry.clone() % n.clone() == T::default(),
+            Test::GreaterThan(n) => compare(n) == Ordering::Greater,
+            Test::LessThan(n) => compare(n) == Ordering::Less,
+            Test::EqualTo(n) => compare(n) == Ordering::Equal,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Test<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Test::DivisibleBy(n) => write!(f, "divisible by {n}"),
+            Test::GreaterThan(n) => write!(f, "greater than {n}"),
+            Test::LessThan(n) => write!(f, "less than {n}"),
+            Test::EqualTo(n) => write!(f, "equal to {n}"),
+        }
+    }
+}
+
+impl<T: FromStr<Err: Debug>> FromStr for Test<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, n) = s.rsplit_once(' ').expect("Wrong Monkey test format");
+        let n = n.parse().expect("Wrong Monkey test operand");
+        Ok(match kind {
+            "divisible by" => Test::DivisibleBy(n),
+            "greater than" => Test::GreaterThan(n),
+            "less than" => Test::LessThan(n),
+            "equal to" => Test::EqualTo(n),
+            _ => panic!("Unexpected Monkey test '{s}'"),
+        })
+    }
 }
 
 #[derive(Debug)]
 struct Monkey<T: Div + Mul + Add + Clone> {
     id: usize,
     items: VecDeque<T>,
-    operation: Op<T, T>,
-
-    /// Tuple: (factor, who to pass if divisible, who to pass if not divisible)
-    div_test: (T, usize, usize),
+    operation: Expr<T, Old>,
+    test: Test<T>,
+    /// Who to throw to when the test passes
+    if_true: usize,
+    /// Who to throw to when it doesn't
+    if_false: usize,
 
     inspections: usize,
 }
 
+/// Step of the simulation, so it can be traced. Displays as the matching line
+/// of the example in the task.
+#[derive(Debug, Clone)]
+enum Event<'a, T> {
+    Turn {
+        monkey: usize,
+    },
+    Inspect {
+        worry: T,
+    },
+    Operation {
+        operation: &'a Expr<T, Old>,
+        worry: T,
+    },
+    Bored {
+        worry: T,
+    },
+    Test {
+        test: &'a Test<T>,
+        passed: bool,
+    },
+    Throw {
+        worry: T,
+        to: usize,
+    },
+}
+
+impl<T: fmt::Display> fmt::Display for Event<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Turn { monkey } => write!(f, "Monkey {monkey}:"),
+            Event::Inspect { worry } => write!(
+                f,
+                "  Monkey inspects an item with a worry level of {worry}."
+            ),
+            Event::Operation { operation, worry } => {
+                write!(f, "    Worry level ")?;
+                match operation {
+                    Expr::BinOp(op, lhs, rhs) => match (op, &**lhs, &**rhs) {
+                        (Operator::Mul, Expr::Var(_), Expr::Var(_)) => {
+                            write!(f, "is multiplied by itself")
+                        }
+                        (Operator::Add, Expr::Var(_), Expr::Const(n)) => {
+                            write!(f, "increases by {n}")
+                        }
+                        (Operator::Mul, Expr::Var(_), Expr::Const(n)) => {
+                            write!(f, "is multiplied by {n}")
+                        }
+                        _ => write!(f, "becomes {operation}"),
+                    },
+                    _ => write!(f, "becomes {operation}"),
+                }?;
+                write!(f, " to {worry}.")
+            }
+            Event::Bored { worry } => write!(
+                f,
+                "    Monkey gets bored with item. Worry level is divided by 3 \
+                 to {worry}."
+            ),
+            Event::Test { test, passed } => {
+                let not = if *passed { "" } else { "not " };
+                write!(f, "    Current worry level is {not}{test}.")
+            }
+            Event::Throw { worry, to } => write!(
+                f,
+                "    Item with worry level {worry} is thrown to monkey {to}."
+            ),
+        }
+    }
+}
+
 // For simulating a single item at a time (optimization)
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct Item {
-    worry_lvl: Num,
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Item<T> {
+    worry_lvl: T,
     monkey: usize,
 }
 
@@ -46,8 +234,8 @@ where
     /// Assumes &str starts with 6 lines with the format:
     /// Monkey #:
     ///  Starting items: #, #, ...
-    ///  Operation: new = old * #
-    ///  Test: divisible by #
+    ///  Operation: new = <expression of old>
+    ///  Test: divisible by # (or greater than, less than, equal to)
     ///    If true: throw to monkey #
     ///    If false: throw to monkey #
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -70,28 +258,22 @@ where
             .map(|n| n.trim().parse::<T>().unwrap())
             .collect();
 
-        let mut operation = lines
+        let operation = lines
             .next()
             .unwrap()
             .trim_start()
-            .trim_start_matches("Operation: new = old ")
-            .split_ascii_whitespace();
-
-        let operation =
-            match (operation.next().unwrap(), operation.next().unwrap()) {
-                ("*", "old") => Op::Square,
-                ("*", num) => Op::Mul(num.trim().parse().unwrap()),
-                ("+", num) => Op::Sum(num.trim().parse().unwrap()),
-                (..) => panic!("Unexpected operation"),
-            };
-
-        let divisor = lines
+            .trim_start_matches("Operation: new = ");
+        let var = |name| (name == "old").then_some(Old);
+        let operation = Expr::parse(operation, var)
+            .unwrap_or_else(|e| panic!("Unexpected operation: {e}"));
+
+        let test = lines
             .next()
             .unwrap()
             .trim_start()
-            .trim_start_matches("Test: divisible by ")
+            .trim_start_matches("Test: ")
             .parse()
-            .expect("Wrong Monkey divisor format");
+            .unwrap();
 
         let if_true = lines
             .next()
@@ -112,250 +294,437 @@ where
             id,
             items,
             operation,
-            div_test: (divisor, if_true, if_false),
+            test,
+            if_true,
+            if_false,
             inspections: 0,
         })
     }
 }
 
+/// Parses every monkey, rejecting the ones that throw to themselves or to
+/// missing monkeys, and the ones that can't work with worry levels of type `T`
+fn parse_monkeys<T>(input: &str) -> Result<Vec<Monkey<T>>, MonkeyError>
+where
+    T: WorryLevel + FromStr<Err: Debug> + fmt::Display,
+{
+    let monkeys: Vec<Monkey<T>> =
+        input.split("\n\n").map(|s| s.parse().unwrap()).collect();
+    for (i, monkey) in monkeys.iter().enumerate() {
+        if monkey.id != i {
+            return Err(MonkeyError::OutOfOrder(monkey.id));
+        }
+        for to in [monkey.if_true, monkey.if_false] {
+            if to == i {
+                return Err(MonkeyError::SelfThrow(i));
+            }
+            if to >= monkeys.len() {
+                return Err(MonkeyError::UnknownTarget { monkey: i, to });
+            }
+        }
+    }
+    if T::EXACT {
+        return Ok(monkeys);
+    }
+
+    for monkey in &monkeys {
+        if monkey.operation.contains_op(Operator::Div) {
+            return Err(MonkeyError::Division(monkey.id));
+        }
+        if !matches!(monkey.test, Test::DivisibleBy(_)) {
+            return Err(MonkeyError::Comparison {
+                monkey: monkey.id,
+                test: monkey.test.to_string(),
+            });
+        }
+    }
+    Ok(monkeys)
+}
+
 impl Monkey<Num> {
-    /// Used for task1
-    fn round(monkeys: &mut [Self]) {
+    /// Used for task1, every step is reported to `events`
+    fn round(monkeys: &mut [Self], events: &mut impl FnMut(Event<'_, Num>)) {
         for i in 0..monkeys.len() {
-            let monkey = &monkeys[i];
-            let dt1 = monkey.div_test.1;
-            let dt2 = monkey.div_test.2;
-
-            let (monkey, m1, m2) =
-                match (dt1.cmp(&monkey.id), dt2.cmp(&monkey.id), dt1.cmp(&dt2))
-                {
-                    (Ordering::Less, Ordering::Greater, _) => {
-                        let (r, rr) = monkeys.split_at_mut(dt2);
-                        let (l, r) = r.split_at_mut(i);
-                        (&mut r[0], &mut l[dt1], &mut rr[0])
-                    }
-                    (Ordering::Greater, Ordering::Less, _) => {
-                        let (r, rr) = monkeys.split_at_mut(dt1);
-                        let (l, r) = r.split_at_mut(i);
-                        (&mut r[0], &mut rr[0], &mut l[dt2])
-                    }
-                    (
-                        Ordering::Greater,
-                        Ordering::Greater,
-                        Ordering::Greater,
-                    ) => {
-                        let (r, rr) = monkeys.split_at_mut(dt1);
-                        let (l, r) = r.split_at_mut(dt2);
-                        (&mut l[i], &mut rr[0], &mut r[0])
-                    }
-                    (Ordering::Greater, Ordering::Greater, Ordering::Less) => {
-                        let (r, rr) = monkeys.split_at_mut(dt2);
-                        let (l, r) = r.split_at_mut(dt1);
-                        (&mut l[i], &mut r[0], &mut rr[0])
-                    }
-                    (Ordering::Less, Ordering::Less, Ordering::Less) => {
-                        let (r, rr) = monkeys.split_at_mut(i);
-                        let (l, r) = r.split_at_mut(dt2);
-                        (&mut rr[0], &mut l[dt1], &mut r[0])
-                    }
-                    (Ordering::Less, Ordering::Less, Ordering::Greater) => {
-                        let (r, rr) = monkeys.split_at_mut(i);
-                        let (l, r) = r.split_at_mut(dt1);
-                        (&mut rr[0], &mut r[0], &mut l[dt2])
-                    }
-                    (o1, o2, o3) => {
-                        eprintln!(
-                            "Add this cmp branch: ({o1:?}, {o2:?}, {o3:?})"
-                        );
-                        panic!("Unexpected monkey division test ruling")
-                    }
-                };
-
-            monkey.inspections += monkey.items.len();
-
-            while let Some(item) = monkey.items.pop_front() {
-                //println!("Inspecting item with worry level {item}");
-                let item = match monkey.operation {
-                    Op::Sum(n) => item + n,
-                    Op::Mul(n) => item * n,
-                    Op::Square => item * item,
-                };
+            // Self throws are rejected by parse_monkeys, so nothing is added
+            // to the items while they're taken out
+            let mut items = std::mem::take(&mut monkeys[i].items);
+            monkeys[i].inspections += items.len();
+            events(Event::Turn { monkey: i });
+
+            while let Some(item) = items.pop_front() {
+                let monkey = &monkeys[i];
+                events(Event::Inspect { worry: item });
+                let item = monkey.operation.eval(&|_| item);
+                events(Event::Operation {
+                    operation: &monkey.operation,
+                    worry: item,
+                });
 
                 let item = item / 3;
-                //println!("Item after operation {0:?}: {item}", monkey.operation);
-
-                if item % monkey.div_test.0 as Num == 0 {
-                    m1.items.push_back(item);
-                } else {
-                    m2.items.push_back(item);
-                }
+                events(Event::Bored { worry: item });
+
+                let passed = monkey.test.check(&item);
+                events(Event::Test {
+                    test: &monkey.test,
+                    passed,
+                });
+                let to = if passed { monkey.if_true } else { monkey.if_false };
+                events(Event::Throw { worry: item, to });
+                monkeys[to].items.push_back(item);
             }
+            // Keeps the allocation for the next round
+            monkeys[i].items = items;
         }
     }
 
-    /// Used for task2
+    /// Items held by every monkey, like in the example after each round
+    fn holdings(monkeys: &[Self], round: usize) -> String {
+        let mut text = format!(
+            "\nAfter round {round}, the monkeys are holding items with these \
+             worry levels:\n"
+        );
+        for monkey in monkeys {
+            let items = monkey.items.iter().map(|i| i.to_string());
+            let items = items.collect::<Vec<_>>().join(", ");
+            text += &format!("\nMonkey {}: {items}", monkey.id);
+        }
+        text
+    }
+}
+
+impl<T: WorryLevel> Monkey<T> {
+    /// Used for task2, `T` must keep the worry levels from growing forever
     fn single_item_sim(
-        item: Item,
-        monkeys: &Vec<Self>,
+        item: Item<T>,
+        monkeys: &[Self],
         rounds: usize,
-        modulo: Num,
     ) -> Vec<usize> {
-        let mut inspection_counts: Vec<usize> = vec![0; monkeys.len()];
-
-        let mut inspected: Vec<bool> = vec![false; monkeys.len()];
-
-        let f = |mut item: Item, mut inspected: Option<&mut Vec<bool>>| {
-            if let Some(ref mut inspected) = inspected {
-                inspected.fill(false);
-            }
-            for monkey in monkeys {
-                if item.monkey != monkey.id {
-                    continue;
-                }
-
-                item.worry_lvl = match monkey.operation {
-                    Op::Sum(n) => item.worry_lvl + n,
-                    Op::Mul(n) => item.worry_lvl * n,
-                    Op::Square => item.worry_lvl * item.worry_lvl,
-                };
-
-                item.worry_lvl %= modulo;
-
-                //println!("Item after operation {0:?}: {item}", monkey.operation);
-                if let Some(ref mut inspected) = inspected {
-                    inspected[item.monkey] = true;
-                }
-
-                if item.worry_lvl % monkey.div_test.0 as Num == 0 {
-                    item.monkey = monkey.div_test.1;
-                } else {
-                    item.monkey = monkey.div_test.2;
-                }
-            }
-            item
-        };
+        let round =
+            |item: &Item<T>| Self::item_round(item, monkeys, &mut |_| {});
+        simulate_n(item, round, rounds).1
+    }
 
-        // Brent's cycle detecting algorithm
-        let mut power = 1;
-        let mut lam = 1;
-        let mut tortoise = item;
-        let mut hare = f(item, None);
-
-        while tortoise != hare {
-            if power == lam {
-                tortoise = hare;
-                power *= 2;
-                lam = 0
+    /// Moves the item along for a round, counting which monkeys inspected it
+    fn item_round(
+        item: &Item<T>,
+        monkeys: &[Self],
+        events: &mut impl FnMut(Event<'_, T>),
+    ) -> (Item<T>, Vec<usize>) {
+        let mut item = item.clone();
+        let mut inspected: Vec<usize> = vec![0; monkeys.len()];
+        for monkey in monkeys {
+            if item.monkey != monkey.id {
+                continue;
             }
-            let next = f(hare, None);
-            hare = next;
-            lam += 1;
+            events(Event::Turn { monkey: monkey.id });
+            events(Event::Inspect {
+                worry: item.worry_lvl.clone(),
+            });
+
+            item.worry_lvl = monkey.operation.eval(&|_| item.worry_lvl.clone());
+            events(Event::Operation {
+                operation: &monkey.operation,
+                worry: item.worry_lvl.clone(),
+            });
+            inspected[item.monkey] += 1;
+
+            let passed = monkey.test.check(&item.worry_lvl);
+            events(Event::Test {
+                test: &monkey.test,
+                passed,
+            });
+            item.monkey = if passed { monkey.if_true } else { monkey.if_false };
+            events(Event::Throw {
+                worry: item.worry_lvl.clone(),
+                to: item.monkey,
+            });
         }
+        (item, inspected)
+    }
+}
 
-        tortoise = item;
-        hare = item;
-        for _ in 0..lam {
-            hare = f(hare, None);
+pub fn task1(input: &str) -> SolutionResult {
+    let mut monkeys: Vec<Monkey<Num>> = match parse_monkeys(input) {
+        Ok(monkeys) => monkeys,
+        Err(err) => {
+            return SolutionResult::Str(format!("Invalid input: {err}"));
         }
+    };
 
-        let mut mu = 0;
-        while tortoise != hare {
-            tortoise = f(tortoise, None);
-            hare = f(hare, None);
-            mu += 1;
+    let verbose = verbose();
+    for round in 1..=20 {
+        Monkey::round(&mut monkeys, &mut |event| {
+            if verbose {
+                println!("{event}");
+            }
+        });
+        if verbose {
+            println!("{}\n", Monkey::holdings(&monkeys, round));
         }
+    }
 
-        let mut item = item;
-        let mut count_inspections_range = |range: Range<usize>| {
-            range.fold(inspection_counts.clone(), |mut vec, _| {
-                item = f(item, Some(&mut inspected));
-                for (i, &inspection) in inspected.iter().enumerate() {
-                    if inspection {
-                        vec[i] += 1;
-                    }
-                }
-                vec
-            })
-        };
-        let mu_inspections = count_inspections_range(0..mu);
-        let cycle_inspections = count_inspections_range(0..lam);
-        let final_inspections =
-            count_inspections_range(0..((rounds - mu) % lam));
-
-        for i in 0..inspection_counts.len() {
-            inspection_counts[i] = cycle_inspections[i] * ((rounds - mu) / lam)
-                + mu_inspections[i]
-                + final_inspections[i];
+    if verbose {
+        for monkey in &monkeys {
+            let (id, count) = (monkey.id, monkey.inspections);
+            println!("Monkey {id} inspected items {count} times.");
         }
-
-        inspection_counts
     }
+
+    let top_2 = monkeys.iter().map(|m| m.inspections).top_k(2);
+
+    SolutionResult::Unsigned(top_2.iter().product())
 }
 
-fn max2<T: PartialOrd + Default>(iter: impl Iterator<Item = T>) -> (T, T) {
-    iter.fold(
-        (Default::default(), Default::default()),
-        |maxes, count| match (count > maxes.0, count > maxes.1) {
-            (true, true) => (count, maxes.0),
-            (false, true) => (maxes.0, count),
-            (true, false) => panic!("Invalid finding max counts comparison"),
-            (false, false) => maxes,
+/// Every item the monkeys start with, to be simulated on its own
+fn starting_items<T>(monkeys: &[Monkey<T>]) -> Vec<Item<T>>
+where
+    T: Div + Mul + Add + Clone,
+{
+    monkeys
+        .iter()
+        .flat_map(|monkey| {
+            monkey.items.iter().map(|item| Item {
+                worry_lvl: item.clone(),
+                monkey: monkey.id,
+            })
+        })
+        .collect()
+}
+
+/// Adds up the inspections per monkey of every item
+fn total_inspections(
+    per_item: impl Iterator<Item = Vec<usize>>,
+    amount: usize,
+) -> Vec<usize> {
+    per_item.fold(
+        [0].repeat(amount),
+        |mut counts: Vec<usize>, item_inspections: Vec<usize>| {
+            for (i, inspections) in item_inspections.iter().enumerate() {
+                counts[i] += inspections;
+            }
+            counts
         },
     )
 }
 
-pub fn task1(input: &str) -> SolutionResult {
-    let mut monkeys: Vec<Monkey<Num>> =
-        input.split("\n\n").map(|s| s.parse().unwrap()).collect();
+/// Starting items with their worry levels modulo the LCM of the monkeys'
+/// divisors. Worry levels are only ever tested for divisibility by them, so
+/// that doesn't change where the items go.
+fn modular_items(monkeys: &[Monkey<Worry>]) -> Vec<Item<Worry>> {
+    let divisors = monkeys.iter().map(|m| match m.test {
+        Test::DivisibleBy(n) => n.value(),
+        _ => unreachable!("rejected by parse_monkeys"),
+    });
+    let modulus = lcm_all(divisors);
+
+    starting_items(monkeys)
+        .into_iter()
+        .map(|item| Item {
+            worry_lvl: Worry::new(item.worry_lvl.value(), modulus),
+            ..item
+        })
+        .collect()
+}
 
-    for _i in 1..=20 {
-        Monkey::round(&mut monkeys);
-        //println!("After Round #{_i}:\n{monkeys:#?}");
-    }
+/// Inspections per monkey after `rounds` rounds without any relief
+fn modular_inspections(monkeys: &[Monkey<Worry>], rounds: usize) -> Vec<usize> {
+    let per_item = modular_items(monkeys)
+        .into_iter()
+        .map(|item| Monkey::single_item_sim(item, monkeys, rounds));
+    total_inspections(per_item, monkeys.len())
+}
 
-    //for monkey in &monkeys {
-    //    println!("Inspections of Monkey{}: {}", monkey.id, monkey.inspections);
-    //}
+/// Inspections per monkey after each of the first `rounds` rounds, without
+/// skipping any. So it also works with worry levels that never repeat.
+fn inspections_per_round<T: WorryLevel>(
+    items: Vec<Item<T>>,
+    monkeys: &[Monkey<T>],
+    rounds: usize,
+) -> Vec<Vec<usize>> {
+    let mut per_round = vec![vec![0; monkeys.len()]; rounds];
+    for mut item in items {
+        let mut item_total = vec![0; monkeys.len()];
+        for counts in &mut per_round {
+            let (next, inspected) =
+                Monkey::item_round(&item, monkeys, &mut |_| {});
+            for (total, inspected) in item_total.iter_mut().zip(inspected) {
+                *total += inspected;
+            }
+            for (count, total) in counts.iter_mut().zip(&item_total) {
+                *count += total;
+            }
+            item = next;
+        }
+    }
+    per_round
+}
 
-    let (first, second) = max2(monkeys.iter().map(|m| m.inspections));
+/// Inspections after the rounds listed in the example of task 2
+fn inspections_report(per_round: &[Vec<usize>]) -> String {
+    let rounds = [1, 20].into_iter().chain((1000..).step_by(1000));
+    let mut text = String::new();
+    for round in rounds.take_while(|&round| round <= per_round.len()) {
+        text += &format!("\n== After round {round} ==\n");
+        for (id, count) in per_round[round - 1].iter().enumerate() {
+            text += &format!("Monkey {id} inspected items {count} times.\n");
+        }
+    }
+    text
+}
 
-    SolutionResult::Unsigned(first * second)
+/// Prints how each item moves during the first round, then the inspections
+/// after some of the rounds, simulating all of them
+fn explain_modular(monkeys: &[Monkey<Worry>], rounds: usize) {
+    for item in modular_items(monkeys) {
+        Monkey::item_round(&item, monkeys, &mut |event| println!("{event}"));
+    }
+    let per_round =
+        inspections_per_round(modular_items(monkeys), monkeys, rounds);
+    print!("{}", inspections_report(&per_round));
 }
 
 pub fn task2(input: &str) -> SolutionResult {
-    let monkeys: Vec<Monkey<Num>> =
-        input.split("\n\n").map(|s| s.parse().unwrap()).collect();
+    // Inputs with other operations or tests are fine for task1, but their
+    // worry levels can't be kept manageable like this
+    let monkeys: Vec<Monkey<Worry>> = match parse_monkeys(input) {
+        Ok(monkeys) => monkeys,
+        Err(err) => {
+            return SolutionResult::Str(format!("Invalid input: {err}"));
+        }
+    };
 
-    let worry_mod = monkeys.iter().fold(1, |mcm, m| mcm * m.div_test.0);
+    if verbose() {
+        explain_modular(&monkeys, 10000);
+    }
+    let inspection_counts = modular_inspections(&monkeys, 10000);
 
-    let items: Vec<Item> = monkeys
-        .iter()
-        .flat_map(|monkey| {
-            monkey.items.iter().map(|&item| Item {
-                worry_lvl: item,
-                monkey: monkey.id,
-            })
-        })
-        .collect();
+    let top_2 = inspection_counts.into_iter().top_k(2);
 
-    //println!("MCM: {worry_mod}");
+    SolutionResult::Unsigned(top_2.iter().product())
+}
 
-    let amount = monkeys.len();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keeping the worry levels modulo the divisors' LCM doesn't change any
+    /// monkey's inspections, checked against the actual worry levels. Those
+    /// keep growing with each squaring, so only for the first rounds.
+    #[test]
+    fn modular_matches_exact() {
+        let input = include_str!("testinput.txt");
+        let rounds = 100;
+
+        let exact: Vec<Monkey<BigUint>> = parse_monkeys(input).unwrap();
+        let exact =
+            inspections_per_round(starting_items(&exact), &exact, rounds);
+        let monkeys: Vec<Monkey<Worry>> = parse_monkeys(input).unwrap();
+        let modular =
+            inspections_per_round(modular_items(&monkeys), &monkeys, rounds);
+
+        for (i, (modular, exact)) in modular.iter().zip(&exact).enumerate() {
+            assert_eq!(modular, exact, "Inspections after round {}", i + 1);
+        }
+        let skipping = modular_inspections(&monkeys, rounds);
+        assert_eq!(&skipping, exact.last().unwrap());
+    }
 
-    let inspection_counts: Vec<usize> = items
-        .into_iter()
-        .map(|item| Monkey::single_item_sim(item, &monkeys, 10000, worry_mod))
-        .fold(
-            [0].repeat(amount),
-            |mut counts: Vec<usize>, item_inspections: Vec<usize>| {
-                for (i, inspections) in item_inspections.iter().enumerate() {
-                    counts[i] += inspections;
-                }
-                counts
-            },
-        );
+    /// Comparison tests only pass on the right side of their operand
+    #[test]
+    fn comparison_tests() {
+        let cases = [
+            ("greater than 5", [false, false, true]),
+            ("less than 5", [true, false, false]),
+            ("equal to 5", [false, true, false]),
+            ("divisible by 5", [false, true, false]),
+        ];
+        for (test, expected) in cases {
+            let test: Test<Num> = test.parse().unwrap();
+            let checks = [4, 5, 6].map(|worry| test.check(&worry));
+            assert_eq!(checks, expected, "{test}");
+        }
+    }
 
-    let (first, second) = max2(inspection_counts.into_iter());
+    fn remix(test: &str, if_true: usize, operation: &str) -> String {
+        format!(
+            "Monkey 0:\n  Starting items: 79, 98\n  Operation: new = \
+             {operation}\n  Test: {test}\n    If true: throw to monkey \
+             {if_true}\n    If false: throw to monkey 1\n\n\
+             Monkey 1:\n  Starting items: 54\n  Operation: new = old + 6\n  \
+             Test: divisible by 19\n    If true: throw to monkey 0\n    \
+             If false: throw to monkey 0"
+        )
+    }
 
-    SolutionResult::Unsigned(first * second)
+    /// Remixed monkeys go through task1, task2 rejects the ones whose worry
+    /// levels it can't keep as remainders
+    #[test]
+    fn remixed_monkeys() {
+        let comparison = remix("greater than 100", 1, "old * 19");
+        let monkeys: Vec<Monkey<Num>> = parse_monkeys(&comparison).unwrap();
+        assert_eq!(monkeys[0].test, Test::GreaterThan(100));
+        assert!(parse_monkeys::<Worry>(&comparison).is_err_and(|e| {
+            e == MonkeyError::Comparison {
+                monkey: 0,
+                test: "greater than 100".to_string(),
+            }
+        }));
+
+        let division = remix("divisible by 23", 1, "(old + 1) / 2");
+        assert!(parse_monkeys::<Num>(&division).is_ok());
+        assert!(parse_monkeys::<Worry>(&division)
+            .is_err_and(|e| e == MonkeyError::Division(0)));
+
+        let same_target = remix("divisible by 23", 1, "old * 19");
+        let mut monkeys: Vec<Monkey<Num>> =
+            parse_monkeys(&same_target).unwrap();
+        Monkey::round(&mut monkeys, &mut |_| {});
+        // Monkey 1 gets both items whatever the test says, and throws them
+        // back along with its own
+        assert_eq!(monkeys[0].items, [20, 168, 208]);
+        assert!(monkeys[1].items.is_empty());
+        let inspections: Vec<_> =
+            monkeys.iter().map(|m| m.inspections).collect();
+        assert_eq!(inspections, [2, 3]);
+
+        let self_throw = remix("divisible by 23", 0, "old * 19");
+        assert!(parse_monkeys::<Num>(&self_throw)
+            .is_err_and(|e| e == MonkeyError::SelfThrow(0)));
+        let missing = remix("divisible by 23", 2, "old * 19");
+        let unknown = MonkeyError::UnknownTarget { monkey: 0, to: 2 };
+        assert!(parse_monkeys::<Num>(&missing).is_err_and(|e| e == unknown));
+    }
+
+    /// The events of the first round, in the words of the example
+    #[test]
+    fn round_narrative_matches_task() {
+        let task = include_str!("task.txt");
+        let start = task.find("Monkey 0:\n  Monkey inspects").unwrap();
+        let end = task.find("\n\nMonkeys 2 and 3 aren't holding").unwrap();
+
+        let mut monkeys: Vec<Monkey<Num>> =
+            parse_monkeys(include_str!("testinput.txt")).unwrap();
+        let mut narrative = String::new();
+        Monkey::round(&mut monkeys, &mut |event| {
+            narrative += &format!("{event}\n");
+        });
+        narrative += &Monkey::holdings(&monkeys, 1);
+
+        assert_eq!(narrative, task[start..end]);
+    }
+
+    /// Inspections after the rounds listed in the example of task 2
+    #[test]
+    fn inspections_report_matches_task() {
+        let task = include_str!("task.txt");
+        let start = task.find("\n== After round 1 ==").unwrap();
+        let end = task.find("\nAfter 10000 rounds").unwrap();
+
+        let monkeys: Vec<Monkey<Worry>> =
+            parse_monkeys(include_str!("testinput.txt")).unwrap();
+        let per_round =
+            inspections_per_round(modular_items(&monkeys), &monkeys, 10000);
+
+        assert_eq!(inspections_report(&per_round), task[start..end]);
+    }
 }
@@ -1,15 +1,26 @@
-use aoc_lib::{structs::grid::Grid, tooling::SolutionResult};
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+use itertools::Itertools;
+
+use aoc_lib::{
+    search::bfs,
+    structs::{
+        grid::{Color, Grid},
+        position::Position,
+    },
+    tooling::{verbose, SolutionResult},
+};
 
 type Num = u32;
 
-use std::collections::VecDeque;
-
 #[derive(Clone, Copy, Debug)]
 pub struct Node {
     x: usize,
     y: usize,
     elevation: Num,
-    parent: Option<(usize, usize)>,
 }
 
 impl PartialEq for Node {
@@ -19,13 +30,17 @@ impl PartialEq for Node {
 }
 
 impl Eq for Node {}
+
+impl Hash for Node {
+    fn hash<H: Hasher>(&self, state: &mut H) { (self.x, self.y).hash(state) }
+}
+
 impl Node {
     pub fn new(x: usize, y: usize, elevation: char) -> Node {
         Node {
             x,
             y,
             elevation: elevation as Num,
-            parent: None,
         }
     }
 }
@@ -49,48 +64,6 @@ fn get_adjacent(grid: &Grid<Node>, origin: Node) -> impl Iterator<Item = Node> {
     .filter_map(move |n| n.filter(|&n| origin.elevation + 1 >= n.elevation))
 }
 
-fn reconstruct_path(grid: &Grid<Node>, end: Node) -> Vec<Node> {
-    let mut current = end;
-    let mut total_path = vec![current];
-    while let Some(parent_xy) = current.parent {
-        // Root's parent is itself
-        if parent_xy.0 == current.x && parent_xy.1 == current.y {
-            break;
-        }
-        current = *grid.get(parent_xy.0, parent_xy.1).unwrap();
-        total_path.push(current)
-    }
-
-    total_path.reverse();
-    total_path
-}
-
-pub fn bfs<F: Fn(Node) -> bool>(
-    mut grid: Grid<Node>,
-    root: Node,
-    reached_goal: F,
-) -> Vec<Node> {
-    let root = grid.get_mut(root.x, root.y).unwrap();
-    root.parent = Some((root.x, root.y));
-
-    let mut q: VecDeque<Node> = [*root].into();
-    while let Some(current) = q.pop_front() {
-        if reached_goal(current) {
-            return reconstruct_path(&grid, current);
-        }
-
-        for neighbor in get_adjacent(&grid, current) {
-            if neighbor.parent.is_none() {
-                let neighbor = grid.get_mut(neighbor.x, neighbor.y).unwrap();
-                neighbor.parent = Some((current.x, current.y));
-                q.push_back(*neighbor);
-            }
-        }
-    }
-
-    panic!("No more adjacent nodes but goal was never reached");
-}
-
 fn input2nodes(input: &str) -> impl Iterator<Item = Node> + '_ {
     input.lines().enumerate().flat_map(|(y, l)| {
         l.chars().enumerate().map(move |(x, c)| {
@@ -137,14 +110,39 @@ fn find_end(input: &str) -> Node {
         .unwrap()
 }
 
+/// Draws the path like the task does: each square of it shows the direction
+/// it's left through and the other squares are `.`. The path is highlighted
+/// in red when the output takes colors.
+fn draw_path(grid: &Grid<Node>, path: &[Node]) -> String {
+    let mut map = Grid::from_fn(grid.rows(), grid.columns(), |_, _| '.');
+    for (from, to) in path.iter().tuple_windows() {
+        map[(from.x, from.y)] = match (to.x.cmp(&from.x), to.y.cmp(&from.y)) {
+            (Ordering::Less, _) => '<',
+            (Ordering::Greater, _) => '>',
+            (_, Ordering::Less) => '^',
+            _ => 'v',
+        };
+    }
+    if let Some(end) = path.last() {
+        map[(end.x, end.y)] = 'E';
+    }
+
+    let path = path.iter().map(|n| Position::new(n.x, n.y));
+    map.overlay().highlight(path, Color::Red).to_string()
+}
+
 pub fn task1(input: &str) -> SolutionResult {
     let grid: Grid<Node> = Grid::parse_grid_with(input, input2nodes);
     let start = find_start(input);
 
     let end = find_end(input);
-    let finish_fn = |n| n == end;
 
-    let path = bfs(grid, start, finish_fn);
+    let path = bfs(start, |&n| get_adjacent(&grid, n), |&n| n == end)
+        .expect("No more adjacent nodes but goal was never reached");
+
+    if verbose() {
+        println!("{}", draw_path(&grid, &path));
+    }
 
     SolutionResult::Unsigned(path.len() - 1)
 }
@@ -180,10 +178,50 @@ fn input2nodes_inverse(input: &str) -> impl Iterator<Item = Node> + '_ {
 pub fn task2(input: &str) -> SolutionResult {
     let grid: Grid<Node> = Grid::parse_grid_with(input, input2nodes_inverse);
 
-    let start = find_end(input);
-    let finish_fn = |n: Node| n.elevation == 'z' as Num;
+    // Take the start from the grid to get its inverted elevation
+    let end = find_end(input);
+    let start = grid[(end.x, end.y)];
+    let finish_fn = |n: &Node| n.elevation == 'z' as Num;
 
-    let path = bfs(grid, start, finish_fn);
+    let path = bfs(start, |&n| get_adjacent(&grid, n), finish_fn)
+        .expect("No more adjacent nodes but goal was never reached");
 
     SolutionResult::Unsigned(path.len() - 1)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The path of the example, walked from the start following the arrows
+    /// of the task, is drawn back the same way
+    #[test]
+    fn path_drawing_matches_task() {
+        let task = include_str!("task.txt");
+        let start = task.find("v..v<<<<").unwrap();
+        let end = start + task[start..].find("\n\n").unwrap();
+        let drawing = &task[start..end];
+        let arrows: Grid<char> = drawing.parse().unwrap();
+
+        let input = include_str!("testinput.txt");
+        let grid: Grid<Node> = Grid::parse_grid_with(input, input2nodes);
+        let mut path = vec![find_start(input)];
+        loop {
+            let node = *path.last().unwrap();
+            let (x, y) = match arrows[(node.x, node.y)] {
+                '<' => (node.x - 1, node.y),
+                '>' => (node.x + 1, node.y),
+                '^' => (node.x, node.y - 1),
+                'v' => (node.x, node.y + 1),
+                _ => break,
+            };
+            let next = grid[(x, y)];
+            assert!(get_adjacent(&grid, node).any(|n| n == next));
+            path.push(next);
+        }
+        assert_eq!(*path.last().unwrap(), find_end(input));
+        assert_eq!(path.len() - 1, 31);
+
+        assert_eq!(draw_path(&grid, &path), drawing);
+    }
+}
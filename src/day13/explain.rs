@@ -0,0 +1,239 @@
+//! Why a pair of packets is (or isn't) in the right order, down to the
+//! element that decided it.
+
+use std::{cmp::Ordering, fmt};
+
+use super::Packet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Left => write!(f, "left"),
+            Side::Right => write!(f, "right"),
+        }
+    }
+}
+
+/// What settled the comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// Two different integers
+    Integers { left: u32, right: u32 },
+    /// One of the lists had no items left while the other still did
+    RanOut(Side),
+    /// Nothing did, the packets are equal
+    Identical,
+}
+
+/// One line of the walk-through, see [`Explanation::walkthrough`]
+#[derive(Debug, Clone)]
+enum Step {
+    Compare {
+        left: String,
+        right: String,
+    },
+    /// An integer compared against a list is converted to a list
+    Promote {
+        side: Side,
+        value: u32,
+    },
+    Verdict,
+}
+
+#[derive(Debug, Clone)]
+pub struct Explanation {
+    pub ordering: Ordering,
+    pub rule: Rule,
+    /// Indices from the outermost lists down to the decisive element. For
+    /// [`Rule::RanOut`], the index the shorter list doesn't have.
+    pub path: Vec<usize>,
+    /// Where integers were converted to lists on the way to the decision
+    pub promotions: Vec<(Vec<usize>, Side)>,
+    /// Every step of the comparison with its nesting depth
+    steps: Vec<(usize, Step)>,
+}
+
+impl Explanation {
+    /// Compares the packets exactly like [`Packet`]'s `Ord`, keeping track
+    /// of each step
+    pub fn new(left: &Packet, right: &Packet) -> Explanation {
+        let mut explanation = Explanation {
+            ordering: Ordering::Equal,
+            rule: Rule::Identical,
+            path: Vec::new(),
+            promotions: Vec::new(),
+            steps: Vec::new(),
+        };
+        let mut path = Vec::new();
+        explanation.ordering = explanation.compare(left, right, 0, &mut path);
+        explanation
+    }
+
+    fn compare(
+        &mut self,
+        left: &Packet,
+        right: &Packet,
+        depth: usize,
+        path: &mut Vec<usize>,
+    ) -> Ordering {
+        self.steps.push((
+            depth,
+            Step::Compare {
+                left: format!("{left:#}"),
+                right: format!("{right:#}"),
+            },
+        ));
+
+        match (left, right) {
+            (&Packet::Integer(l), &Packet::Integer(r)) => {
+                let ordering = l.cmp(&r);
+                if ordering.is_ne() {
+                    self.decide(Rule::Integers { left: l, right: r }, path);
+                    self.steps.push((depth + 1, Step::Verdict));
+                }
+                ordering
+            }
+            (&Packet::Integer(value), list @ Packet::List(_)) => {
+                self.promote(Side::Left, value, list, depth, path)
+            }
+            (list @ Packet::List(_), &Packet::Integer(value)) => {
+                self.promote(Side::Right, value, list, depth, path)
+            }
+            (Packet::List(l), Packet::List(r)) => {
+                for i in 0.. {
+                    let (l, r) = match (l.get(i), r.get(i)) {
+                        (Some(l), Some(r)) => (l, r),
+                        (None, None) => return Ordering::Equal,
+                        (None, Some(_)) => {
+                            return self.ran_out(Side::Left, i, depth, path);
+                        }
+                        (Some(_), None) => {
+                            return self.ran_out(Side::Right, i, depth, path);
+                        }
+                    };
+                    path.push(i);
+                    let ordering = self.compare(l, r, depth + 1, path);
+                    path.pop();
+                    if ordering.is_ne() {
+                        return ordering;
+                    }
+                }
+                unreachable!()
+            }
+        }
+    }
+
+    /// Retries the comparison with the integer on `side` converted to a list
+    fn promote(
+        &mut self,
+        side: Side,
+        value: u32,
+        list: &Packet,
+        depth: usize,
+        path: &mut Vec<usize>,
+    ) -> Ordering {
+        self.steps.push((depth + 1, Step::Promote { side, value }));
+        let promoted = Packet::List(vec![Packet::Integer(value)]);
+        let ordering = match side {
+            Side::Left => self.compare(&promoted, list, depth + 1, path),
+            Side::Right => self.compare(list, &promoted, depth + 1, path),
+        };
+        if ordering.is_ne() {
+            self.promotions.push((path.clone(), side));
+        }
+        ordering
+    }
+
+    /// The list on `side` has no item at `index` while the other one does
+    fn ran_out(
+        &mut self,
+        side: Side,
+        index: usize,
+        depth: usize,
+        path: &mut Vec<usize>,
+    ) -> Ordering {
+        path.push(index);
+        self.decide(Rule::RanOut(side), path);
+        path.pop();
+        self.steps.push((depth + 1, Step::Verdict));
+        match side {
+            Side::Left => Ordering::Less,
+            Side::Right => Ordering::Greater,
+        }
+    }
+
+    fn decide(&mut self, rule: Rule, path: &[usize]) {
+        self.rule = rule;
+        self.path = path.to_vec();
+    }
+
+    /// Every step as a bulleted list, like the examples in the task
+    pub fn walkthrough(&self) -> String {
+        let lines = self.steps.iter().map(|(depth, step)| {
+            let line = match step {
+                Step::Compare { left, right } => {
+                    format!("Compare {left} vs {right}")
+                }
+                Step::Promote { side, value } => format!(
+                    "Mixed types; convert {side} to [{value}] and retry \
+                     comparison"
+                ),
+                Step::Verdict => self.verdict(),
+            };
+            format!("{}- {line}", "  ".repeat(*depth))
+        });
+        lines.collect::<Vec<_>>().join("\n")
+    }
+
+    fn verdict(&self) -> String {
+        let reason = match self.rule {
+            Rule::Integers { .. } if self.ordering.is_lt() => {
+                "Left side is smaller"
+            }
+            Rule::Integers { .. } => "Right side is smaller",
+            Rule::RanOut(Side::Left) => "Left side ran out of items",
+            Rule::RanOut(Side::Right) => "Right side ran out of items",
+            Rule::Identical => return "Both packets are equal".to_string(),
+        };
+        let not = if self.ordering.is_lt() { "" } else { "not " };
+        format!("{reason}, so inputs are {not}in the right order")
+    }
+}
+
+/// Indices like `[1][0]`
+fn write_path(f: &mut fmt::Formatter<'_>, path: &[usize]) -> fmt::Result {
+    if path.is_empty() {
+        return write!(f, "the top level");
+    }
+    path.iter().try_for_each(|i| write!(f, "[{i}]"))
+}
+
+/// Just the decisive element, like `[1][0]: 2 < 4`
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write_path(f, &self.path)?;
+            write!(f, ": ")?;
+        }
+        match self.rule {
+            Rule::Integers { left, right } => {
+                let sign = if left < right { '<' } else { '>' };
+                write!(f, "{left} {sign} {right}")?;
+            }
+            Rule::RanOut(side) => write!(f, "{side} side ran out of items")?,
+            Rule::Identical => write!(f, "identical packets")?,
+        }
+        for (path, side) in self.promotions.iter().rev() {
+            write!(f, ", {side} integer at ")?;
+            write_path(f, path)?;
+            write!(f, " converted to a list")?;
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,20 @@
-use std::{cmp::Ordering, fmt, str::FromStr, string::ParseError};
+use std::{cmp::Ordering, fmt, str::FromStr};
 
-use aoc_lib::tooling::SolutionResult;
+use thiserror::Error;
 
+use aoc_lib::{
+    parsing::json::{Json, ParseJsonError},
+    tooling::{verbose, SolutionResult},
+};
+
+mod explain;
+mod stream;
+
+use explain::Explanation;
+
+/// Either a list or an integer, written like a JSON array of integers
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum Packet {
+pub enum Packet {
     List(Vec<Packet>),
     Integer(u32),
 }
@@ -28,16 +39,19 @@ impl Ord for Packet {
     }
 }
 
+/// `{}` separates the items with ", ", `{:#}` writes the packet exactly like
+/// the input does
 impl fmt::Display for Packet {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Packet::List(vec) => {
+                let separator = if f.alternate() { "," } else { ", " };
                 write!(f, "[")?;
                 for (i, p) in vec.iter().enumerate() {
-                    write!(f, "{}", p)?;
-                    if i < vec.len() - 1 {
-                        write!(f, ", ")?;
+                    if i > 0 {
+                        write!(f, "{separator}")?;
                     }
+                    p.fmt(f)?;
                 }
                 write!(f, "]")
             }
@@ -46,100 +60,242 @@ impl fmt::Display for Packet {
     }
 }
 
-impl FromStr for Packet {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.chars().next().unwrap() == '[' {
-            let mut list = Vec::with_capacity(16);
+#[derive(Error, Debug, PartialEq)]
+pub enum PacketError {
+    #[error("invalid JSON: {0}")]
+    Json(#[from] ParseJsonError),
+    #[error("packets only hold lists and integers, found {0}")]
+    NotAPacket(Json),
+    #[error("{0} is not an integer from 0 to {max}", max = u32::MAX)]
+    InvalidInteger(f64),
+}
 
-            let mut chars = s.char_indices();
+impl From<&Packet> for Json {
+    fn from(packet: &Packet) -> Self {
+        match packet {
+            Packet::List(list) => {
+                Json::Array(list.iter().map(Json::from).collect())
+            }
+            &Packet::Integer(i) => Json::Number(i.into()),
+        }
+    }
+}
 
-            chars.next(); // get rid of initial '['
+impl TryFrom<&Json> for Packet {
+    type Error = PacketError;
 
-            'outer: while let Some((i, c)) = chars.next() {
-                if c == ',' {
-                    continue;
-                }
-                if c == '[' {
-                    let mut nesting = 0;
-                    while let Some((end_i, c)) = chars.next() {
-                        if c == '[' {
-                            nesting += 1;
-                        } else if c == ']' {
-                            if nesting == 0 {
-                                list.push(s[i..=end_i].parse().unwrap());
-                                break;
-                            } else {
-                                nesting -= 1;
-                            }
-                        }
-                    }
-                } else {
-                    while let Some((end_i, c)) = chars.next() {
-                        if c == ',' || c == ']' {
-                            list.push(s[i..end_i].parse().unwrap());
-                            if c == ']' {
-                                break 'outer;
-                            }
-                            break;
-                        }
-                    }
+    fn try_from(json: &Json) -> Result<Self, Self::Error> {
+        match *json {
+            Json::Array(ref items) => {
+                let list = items.iter().map(Packet::try_from);
+                Ok(Packet::List(list.collect::<Result<_, _>>()?))
+            }
+            Json::Number(n) => {
+                let integer =
+                    n.fract() == 0.0 && (0.0..=u32::MAX.into()).contains(&n);
+                if !integer {
+                    return Err(PacketError::InvalidInteger(n));
                 }
+                Ok(Packet::Integer(n as u32))
             }
+            _ => Err(PacketError::NotAPacket(json.clone())),
+        }
+    }
+}
+
+/// Any JSON made of arrays and integers, whitespace included
+impl FromStr for Packet {
+    type Err = PacketError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Packet::try_from(&s.parse::<Json>()?)
+    }
+}
 
-            Ok(Packet::List(list))
-        } else {
-            Ok(Packet::Integer(s.trim_matches(',').parse().unwrap()))
+impl Packet {
+    /// Same format as the puzzle input, which is also the compact JSON form.
+    /// Two packets are equal exactly when their canonical forms are.
+    pub fn to_canonical(&self) -> String { format!("{self:#}") }
+}
+
+/// Sorts `packets` along with the `dividers`, giving back the sorted packets
+/// and the index each divider ended up at. Dividers go after any packets
+/// equal to them.
+pub fn sort_packets(
+    packets: Vec<Packet>,
+    dividers: &[Packet],
+) -> (Vec<Packet>, Vec<usize>) {
+    let dividers = dividers.iter().cloned().enumerate();
+    let mut tagged: Vec<(Packet, Option<usize>)> = packets
+        .into_iter()
+        .map(|packet| (packet, None))
+        .chain(dividers.map(|(i, divider)| (divider, Some(i))))
+        .collect();
+    // Stable, so the dividers stay after their equals
+    tagged.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut positions = Vec::new();
+    let mut sorted = Vec::with_capacity(tagged.len());
+    for (i, (packet, tag)) in tagged.into_iter().enumerate() {
+        if let Some(divider) = tag {
+            positions.push((divider, i));
         }
+        sorted.push(packet);
     }
+    positions.sort();
+    (sorted, positions.into_iter().map(|(_, i)| i).collect())
 }
 
-pub fn task1(input: &str) -> SolutionResult {
-    let pairs: Vec<[Packet; 2]> = input
+fn pairs(input: &str) -> impl Iterator<Item = (&str, &str)> {
+    input
         .split("\n\n")
-        .map(|lines: &str| {
-            let l = lines.split_once('\n').unwrap();
-            [l.0.parse().unwrap(), l.1.parse().unwrap()]
-        })
+        .map(|pair| pair.split_once('\n').unwrap())
+}
+
+/// Sum of the (1-based) indices of the pairs already in the right order
+fn ordered_pairs_sum(input: &str) -> usize {
+    pairs(input)
+        .enumerate()
+        .filter(|(_, (left, right))| stream::compare(left, right).is_lt())
+        .map(|(i, _)| i + 1)
+        .sum()
+}
+
+/// [`ordered_pairs_sum`] on parsed packets
+fn ordered_pairs_sum_tree(input: &str) -> usize {
+    let pairs: Vec<[Packet; 2]> = pairs(input)
+        .map(|(left, right)| [left.parse().unwrap(), right.parse().unwrap()])
         .collect();
 
     let mut res = 0;
     for (i, pair) in pairs.iter().enumerate() {
-        //println!(
-        //    "The lists are in the {} order",
-        //    if pair[0] < pair[1] { "right" } else { "wrong" }
-        //);
-        //println!("{}\n{}\n", pair[0], pair[1]);
         if pair[0] < pair[1] {
             res += i + 1
         }
     }
+    res
+}
+
+/// Product of the (1-based) positions of the divider packets once every
+/// packet is sorted. Only the packets before each divider matter, so nothing
+/// is actually sorted.
+fn decoder_key(input: &str) -> usize {
+    let packets: Vec<&str> = input.lines().filter(|l| !l.is_empty()).collect();
+    let position = |divider: &str| {
+        packets
+            .iter()
+            .filter(|p| stream::compare(p, divider).is_lt())
+            .count()
+            + 1
+    };
 
-    SolutionResult::Unsigned(res)
+    // The [[2]] divider also comes before [[6]]
+    position("[[2]]") * (position("[[6]]") + 1)
 }
 
-pub fn task2(input: &str) -> SolutionResult {
-    let mut packets: Vec<Packet> = input
+/// [`decoder_key`] sorting the parsed packets
+fn decoder_key_tree(input: &str) -> usize {
+    let packets: Vec<Packet> = input
         .lines()
         .filter(|l| !l.is_empty())
         .map(|l: &str| l.parse().unwrap())
         .collect();
+    let dividers = ["[[2]]", "[[6]]"].map(|d| d.parse().unwrap());
+
+    let (_, positions) = sort_packets(packets, &dividers);
+
+    positions.iter().map(|i| i + 1).product()
+}
+
+/// Explanation of every pair's ordering
+fn explanations(input: &str) -> impl Iterator<Item = Explanation> + '_ {
+    pairs(input).map(|(left, right)| {
+        Explanation::new(&left.parse().unwrap(), &right.parse().unwrap())
+    })
+}
+
+/// Every comparison step of every pair, like the example in the task
+fn walkthroughs(input: &str) -> String {
+    let pairs = explanations(input).enumerate().map(|(i, explanation)| {
+        format!("== Pair {} ==\n{}", i + 1, explanation.walkthrough())
+    });
+    pairs.collect::<Vec<_>>().join("\n\n")
+}
+
+pub fn task1(input: &str) -> SolutionResult {
+    if verbose() {
+        println!("{}\n", walkthroughs(input));
+        for (i, explanation) in explanations(input).enumerate() {
+            println!("Pair {}: {explanation}", i + 1);
+        }
+    }
+    SolutionResult::Unsigned(ordered_pairs_sum(input))
+}
+
+pub fn task2(input: &str) -> SolutionResult {
+    SolutionResult::Unsigned(decoder_key(input))
+}
+
+/// [`task1`] on parsed packets, see `--bench`
+pub fn task1_tree(input: &str) -> SolutionResult {
+    SolutionResult::Unsigned(ordered_pairs_sum_tree(input))
+}
 
-    // Divider packets
-    let divider_2 = Packet::List(vec![Packet::List(vec![Packet::Integer(2)])]);
-    let divider_6 = Packet::List(vec![Packet::List(vec![Packet::Integer(6)])]);
-    packets.push(divider_2.clone());
-    packets.push(divider_6.clone());
+/// [`task2`] on parsed packets, see `--bench`
+pub fn task2_tree(input: &str) -> SolutionResult {
+    SolutionResult::Unsigned(decoder_key_tree(input))
+}
 
-    packets.sort();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_lib::random::Rng;
 
-    let mut res = 1;
-    for (i, packet) in packets.iter().enumerate() {
-        if *packet == divider_2 || *packet == divider_6 {
-            res *= i + 1;
+    /// Packet with few small values and short lists, so that pairs of them
+    /// often share a prefix and need mixed comparisons
+    fn random_packet(rng: &mut Rng, depth: usize) -> String {
+        if depth > 0 && (depth > 4 || rng.below(3) == 0) {
+            return [0, 1, 2, 10][rng.below(4)].to_string();
         }
+        let items: Vec<String> = (0..rng.below(4))
+            .map(|_| random_packet(rng, depth + 1))
+            .collect();
+        format!("[{}]", items.join(","))
     }
 
-    SolutionResult::Unsigned(res)
+    /// Every comparator agrees on random pairs of packets
+    #[test]
+    fn comparators_agree() {
+        let mut rng = Rng::new(0x2022_1213);
+        for _ in 0..10_000 {
+            let (left, right) =
+                (random_packet(&mut rng, 0), random_packet(&mut rng, 0));
+            let left_tree: Packet = left.parse().unwrap();
+            let right_tree: Packet = right.parse().unwrap();
+            let tree = left_tree.cmp(&right_tree);
+            let streaming = stream::compare(&left, &right);
+            assert_eq!(tree, streaming, "Comparing {left} and {right}");
+            let explained = Explanation::new(&left_tree, &right_tree);
+            assert_eq!(tree, explained.ordering, "Explaining {left}, {right}");
+            assert_eq!(left_tree.to_canonical(), left);
+            assert_eq!(stream::compare(&left, &left), Ordering::Equal);
+        }
+    }
+
+    #[test]
+    fn tree_variants_match() {
+        let input = include_str!("testinput.txt");
+        assert_eq!(ordered_pairs_sum_tree(input), ordered_pairs_sum(input));
+        assert_eq!(decoder_key_tree(input), decoder_key(input));
+    }
+
+    #[test]
+    fn walkthroughs_match_task() {
+        let task = include_str!("task.txt");
+        let start = task.find("== Pair 1 ==").unwrap();
+        let end = task.find("\n\nWhat are the indices").unwrap();
+
+        let input = include_str!("testinput.txt");
+        assert_eq!(walkthroughs(input), task[start..end]);
+    }
 }
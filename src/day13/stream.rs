@@ -0,0 +1,134 @@
+//! Packet comparison straight from the input text, without building any
+//! [`Packet`](super::Packet) trees.
+
+use std::cmp::Ordering;
+
+/// What a packet is made of, commas and whitespace only separate them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Integer(u32),
+}
+
+/// Reads the tokens of a packet one at a time. An integer that has to be
+/// compared against a list gets wrapped in virtual brackets, which only exist
+/// as counters.
+struct Tokens<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    /// Virtual `[` around the integer at `pos`
+    wrapped: usize,
+    /// Virtual `]` still due after the last integer read
+    closing: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(packet: &'a str) -> Tokens<'a> {
+        Tokens {
+            bytes: packet.as_bytes(),
+            pos: 0,
+            wrapped: 0,
+            closing: 0,
+        }
+    }
+
+    /// Next token without consuming it, `None` at the end of the packet
+    fn peek(&mut self) -> Option<Token> {
+        if self.closing > 0 {
+            return Some(Token::Close);
+        }
+        let separator = |b: &u8| *b == b',' || b.is_ascii_whitespace();
+        while self.bytes.get(self.pos).is_some_and(separator) {
+            self.pos += 1;
+        }
+        match *self.bytes.get(self.pos)? {
+            b'[' => Some(Token::Open),
+            b']' => Some(Token::Close),
+            _ => Some(Token::Integer(self.integer().0)),
+        }
+    }
+
+    /// Moves past the token [`Tokens::peek`] returned
+    fn advance(&mut self) {
+        if self.closing > 0 {
+            self.closing -= 1;
+            return;
+        }
+        match self.bytes[self.pos] {
+            b'[' | b']' => self.pos += 1,
+            _ => {
+                self.pos += self.integer().1;
+                self.closing = std::mem::take(&mut self.wrapped);
+            }
+        }
+    }
+
+    /// Promotes the integer at `pos` to a list containing only it
+    fn wrap(&mut self) { self.wrapped += 1; }
+
+    /// Value and amount of digits of the integer at `pos`. Panics if there's
+    /// anything else there, since the packet isn't well formed.
+    fn integer(&self) -> (u32, usize) {
+        let digits = self.bytes[self.pos..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit());
+        let (value, len) = digits.fold((0, 0), |(n, len), &d| {
+            (n * 10 + u32::from(d - b'0'), len + 1)
+        });
+        if len == 0 {
+            let found = char::from(self.bytes[self.pos]);
+            panic!("Unexpected {found:?} at position {} of a packet", self.pos);
+        }
+        (value, len)
+    }
+}
+
+/// Same ordering as comparing the parsed packets, walking both strings at the
+/// same time. Expects well formed packets.
+pub fn compare(left: &str, right: &str) -> Ordering {
+    let mut left = Tokens::new(left);
+    let mut right = Tokens::new(right);
+
+    loop {
+        match (left.peek(), right.peek()) {
+            (None, None) => return Ordering::Equal,
+            (Some(Token::Integer(l)), Some(Token::Integer(r))) if l != r => {
+                return l.cmp(&r);
+            }
+            (Some(Token::Open), Some(Token::Integer(_))) => {
+                left.advance();
+                right.wrap();
+                continue;
+            }
+            (Some(Token::Integer(_)), Some(Token::Open)) => {
+                left.wrap();
+                right.advance();
+                continue;
+            }
+            // The same kind of token on both sides
+            (Some(l), Some(r)) if l == r => {}
+            (Some(Token::Close), _) | (None, _) => return Ordering::Less,
+            (_, Some(Token::Close)) | (_, None) => return Ordering::Greater,
+            (Some(_), Some(_)) => unreachable!("every other pair is handled"),
+        }
+        left.advance();
+        right.advance();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whitespace_separates_like_commas() {
+        assert_eq!(compare("[1, 2]", "[1, 3]"), Ordering::Less);
+        assert_eq!(compare("[ [1] ,4 ]", "[[1],4]"), Ordering::Equal);
+        assert_eq!(compare("[[2]]\r", "[3]\r"), Ordering::Less);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unexpected 'x' at position 1")]
+    fn malformed_packet() { compare("[x]", "[1]"); }
+}
@@ -1,10 +1,17 @@
 use std::cmp::Ordering;
 
 use aoc_lib::{
-    structs::{grid::Grid, position::Position},
-    tooling::SolutionResult,
+    image::{FrameWriter, ImageFormat, Rgb},
+    structs::{
+        bitgrid::BitGrid,
+        grid::{Grid, GridTile},
+        position::Position,
+    },
+    tooling::{frames_dir, verbose, SolutionResult},
 };
 
+pub mod tiles;
+
 const MIN_X: usize = 300;
 const MAX_X: usize = 700;
 const MIN_Y: usize = 0;
@@ -15,78 +22,140 @@ const MAX_Y: usize = 200;
 //const MIN_Y: usize = 0;
 //const MAX_Y: usize = 12;
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+/// Contents of a tile of the cave, only used for drawing it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Tile {
-    Sand,
     Air,
-    Wall,
+    Rock,
+    Sand,
 }
 
-impl Default for Tile {
-    fn default() -> Self { Tile::Air }
+impl GridTile for Tile {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            ' ' => Some(Tile::Air),
+            '#' => Some(Tile::Rock),
+            '𐬽' => Some(Tile::Sand),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Tile::Air => ' ',
+            Tile::Rock => '#',
+            Tile::Sand => '𐬽',
+        }
+    }
 }
 
-/// Spawns a grain of sand and modifies the grid changing an Air tile with a
-/// Sand tile in its final resting position, and returns said Position. If the
-/// Sand flows off to the abyss then the grid is left untouched and the position
-/// will have the last position of the sand before it left the grid.
-fn spawn_sand(
-    grid: &mut Grid<Tile>,
-    spawn_position: Position<usize>,
-) -> Position<usize> {
-    if spawn_position.x >= grid.columns() || spawn_position.y >= grid.rows() {
-        panic!("Spawn position out of bounds, the dimensions are {:?} but the spawn is {spawn_position}", (grid.columns(), grid.rows()) );
+/// Slice of the cave, as one bit per tile for the rock and another for the
+/// tiles blocked by either rock or resting sand. Any other tile is air.
+struct Cave {
+    rock: BitGrid,
+    /// Transposed, each column of the cave is a row of bits so finding where
+    /// a grain of sand lands when falling straight down is a bit scan.
+    blocked: BitGrid,
+}
+
+impl Cave {
+    fn new(rows: usize, columns: usize) -> Cave {
+        Cave {
+            rock: BitGrid::new(rows, columns),
+            blocked: BitGrid::new(columns, rows),
+        }
+    }
+
+    fn rows(&self) -> usize { self.rock.rows() }
+
+    fn columns(&self) -> usize { self.rock.columns() }
+
+    /// Whether the tile has rock or sand on it, `None` if it's out of bounds
+    fn blocked(&self, x: usize, y: usize) -> Option<bool> {
+        self.blocked.get(y, x)
+    }
+
+    /// Depth of the first blocked tile below `pos`, if any
+    fn first_blocked_below(&self, pos: Position<usize>) -> Option<usize> {
+        self.blocked.next_one_in_row(pos.x, pos.y + 1)
     }
-    if grid[spawn_position] != Tile::Air {
-        panic!("Attempted to spawn sand at a clogged spawn position");
+
+    fn block(&mut self, pos: Position<usize>) {
+        self.blocked.set(pos.y, pos.x, true);
     }
 
-    let mut current_pos = spawn_position;
+    fn add_rock(&mut self, x: usize, y: usize) {
+        self.rock.set(x, y, true);
+        self.block(Position::new(x, y));
+    }
+
+    fn to_grid(&self) -> Grid<Tile> {
+        Grid::from_fn(self.rows(), self.columns(), |x, y| {
+            let rock = self.rock.contains(Position::new(x, y));
+            match (rock, self.blocked(x, y)) {
+                (true, _) => Tile::Rock,
+                (_, Some(true)) => Tile::Sand,
+                _ => Tile::Air,
+            }
+        })
+    }
+}
+
+/// Spawns a grain of sand and modifies the cave putting sand on an air tile in
+/// its final resting position, and returns said Position. If the sand flows
+/// off to the abyss then the cave is left untouched and the position will
+/// have the last position of the sand before it left the cave.
+///
+/// Every grain follows the same route as the previous one until the tile where
+/// that one came to rest, so `path` keeps the route of the previous grain
+/// (starting at the spawn position) and the new one starts from its end.
+fn spawn_sand(
+    cave: &mut Cave,
+    path: &mut Vec<Position<usize>>,
+) -> Position<usize> {
+    let mut current_pos = *path
+        .last()
+        .expect("Attempted to spawn sand at a clogged spawn position");
 
     loop {
-        let down_left = grid.get(
+        let down_left = cave.blocked(
             current_pos
                 .x
                 .checked_sub(1)
-                .unwrap_or_else(|| grid.columns()),
+                .unwrap_or_else(|| cave.columns()),
             current_pos.y + 1,
         );
-        let down_center = grid.get(current_pos.x, current_pos.y + 1);
-        let down_right = grid.get(current_pos.x + 1, current_pos.y + 1);
+        let down_center = cave.blocked(current_pos.x, current_pos.y + 1);
+        let down_right = cave.blocked(current_pos.x + 1, current_pos.y + 1);
 
         //println!("Current: {current_pos}, DL: {down_left:?}, DC: {down_center:?}, DR: {down_right:?}");
 
         match (down_left, down_center, down_right) {
             // Move down straight
-            (_, Some(Tile::Air), _) => {
-                while let Some(Tile::Air) =
-                    grid.get(current_pos.x, current_pos.y + 1)
-                {
-                    //println!("Freefalling! {current_pos}");
-                    current_pos.y += 1;
-                }
+            (_, Some(false), _) => {
+                //println!("Freefalling! {current_pos}");
+                current_pos.y = match cave.first_blocked_below(current_pos) {
+                    Some(y) => y - 1,
+                    None => cave.rows() - 1,
+                };
+                path.push(current_pos);
             }
             // Move down left
-            (Some(Tile::Air), Some(Tile::Wall | Tile::Sand), _) => {
+            (Some(false), Some(true), _) => {
                 current_pos.x -= 1;
                 current_pos.y += 1;
+                path.push(current_pos);
             }
             // Move down right
-            (
-                Some(Tile::Wall | Tile::Sand),
-                Some(Tile::Wall | Tile::Sand),
-                Some(Tile::Air),
-            ) => {
+            (Some(true), Some(true), Some(false)) => {
                 current_pos.x += 1;
                 current_pos.y += 1;
+                path.push(current_pos);
             }
             // Blocked, stop
-            (
-                Some(Tile::Wall | Tile::Sand),
-                Some(Tile::Wall | Tile::Sand),
-                Some(Tile::Wall | Tile::Sand),
-            ) => {
-                grid[current_pos] = Tile::Sand;
+            (Some(true), Some(true), Some(true)) => {
+                cave.block(current_pos);
+                path.pop();
                 break;
             }
             // Reached bottom, stop
@@ -94,18 +163,14 @@ fn spawn_sand(
                 break;
             }
             // Overflow to left, stop at bottom left corner
-            (None, Some(Tile::Wall | Tile::Sand), _) => {
-                current_pos = Position::new(0, grid.rows() - 1);
+            (None, Some(true), _) => {
+                current_pos = Position::new(0, cave.rows() - 1);
                 break;
             }
             // Overflow to right, stop at bottom right corner
-            (
-                Some(Tile::Wall | Tile::Sand),
-                Some(Tile::Wall | Tile::Sand),
-                None,
-            ) => {
+            (Some(true), Some(true), None) => {
                 current_pos =
-                    Position::new(grid.columns() - 1, grid.rows() - 1);
+                    Position::new(cave.columns() - 1, cave.rows() - 1);
                 break;
             }
         }
@@ -114,7 +179,7 @@ fn spawn_sand(
     current_pos
 }
 
-fn parse(input: &str) -> Grid<Tile> {
+fn parse(input: &str) -> Cave {
     let coords_iter = input.lines().map(|l| {
         l.split(" -> ")
             .map(|coords| {
@@ -127,7 +192,7 @@ fn parse(input: &str) -> Grid<Tile> {
             .peekable()
     });
 
-    let mut grid = Grid::new(MAX_Y - MIN_Y, MAX_X - MIN_X);
+    let mut cave = Cave::new(MAX_Y - MIN_Y, MAX_X - MIN_X);
 
     for mut wall in coords_iter {
         let mut current = wall.next().unwrap();
@@ -135,22 +200,22 @@ fn parse(input: &str) -> Grid<Tile> {
             match (next.x.cmp(&current.x), next.y.cmp(&current.y)) {
                 (Ordering::Greater, Ordering::Equal) => {
                     for x in current.x..=next.x {
-                        grid[current.y][x] = Tile::Wall;
+                        cave.add_rock(x, current.y);
                     }
                 }
                 (Ordering::Equal, Ordering::Greater) => {
                     for y in current.y..=next.y {
-                        grid[y][current.x] = Tile::Wall;
+                        cave.add_rock(current.x, y);
                     }
                 }
                 (Ordering::Less, Ordering::Equal) => {
                     for x in next.x..=current.x {
-                        grid[current.y][x] = Tile::Wall;
+                        cave.add_rock(x, current.y);
                     }
                 }
                 (Ordering::Equal, Ordering::Less) => {
                     for y in next.y..=current.y {
-                        grid[y][current.x] = Tile::Wall;
+                        cave.add_rock(current.x, y);
                     }
                 }
                 (Ordering::Equal, Ordering::Equal) => {
@@ -167,102 +232,121 @@ fn parse(input: &str) -> Grid<Tile> {
         }
     }
 
-    grid
+    cave
 }
 
-#[allow(dead_code)]
-fn draw_grid(grid: &Grid<Tile>) {
-    for row in grid.iter_rows() {
-        for tile in row {
-            print!(
-                "{}",
-                match tile {
-                    Tile::Air => ' ',
-                    Tile::Sand => '𐬽',
-                    Tile::Wall => '#',
-                }
-            )
+fn draw_cave(cave: &Cave) { println!("{}", cave.to_grid()); }
+
+fn tile_color(tile: &Tile) -> Rgb {
+    match tile {
+        Tile::Air => [20, 20, 30],
+        Tile::Rock => [110, 110, 110],
+        Tile::Sand => [230, 200, 120],
+    }
+}
+
+/// Animation of the sand falling, as numbered images in the `day14`
+/// subdirectory of the one given with `--frames`
+fn frame_writer() -> Option<FrameWriter> {
+    let dir = frames_dir()?.join("day14");
+    FrameWriter::new(&dir, "sand", ImageFormat::Png, 2)
+        .inspect_err(|err| eprintln!("Not saving frames: {err}"))
+        .ok()
+}
+
+/// Writes the cave as the next frame of the animation, giving up on the
+/// animation if that fails
+fn save_frame(frames: &mut Option<FrameWriter>, cave: &Cave) {
+    if let Some(writer) = frames {
+        if let Err(err) = writer.write(&cave.to_grid(), tile_color) {
+            eprintln!("Stopped saving frames: {err}");
+            *frames = None;
         }
-        println!();
     }
 }
 
 pub fn task1(input: &str) -> SolutionResult {
-    let mut grid: Grid<Tile> = parse(input);
+    let mut cave = parse(input);
 
     let spawn_position = Position::new(500, 0) - Position::new(MIN_X, MIN_Y);
+    if spawn_position.x >= cave.columns() || spawn_position.y >= cave.rows() {
+        panic!("Spawn position out of bounds, the dimensions are {:?} but the spawn is {spawn_position}", (cave.columns(), cave.rows()) );
+    }
+
+    let mut frames = frame_writer();
 
+    let mut path = vec![spawn_position];
     let mut counter = 0;
-    let mut resting_position = spawn_sand(&mut grid, spawn_position);
+    let mut resting_position = spawn_sand(&mut cave, &mut path);
 
     while resting_position.y + 1 < MAX_Y {
         //println!("Sand finished on {resting_position}");
         counter += 1;
-        resting_position = spawn_sand(&mut grid, spawn_position);
+        resting_position = spawn_sand(&mut cave, &mut path);
+        save_frame(&mut frames, &cave);
     }
 
-    //draw_grid(&grid);
+    if verbose() {
+        draw_cave(&cave);
+    }
 
     SolutionResult::Unsigned(counter)
 }
 
+/// Fills with sand the pyramid under the spawn position, a row at a time: sand
+/// reaches every tile right below or diagonally below sand, unless there's
+/// rock on it. Returns the amount of tiles with sand.
 fn fill_pyramid(
-    grid: &mut Grid<Tile>,
+    cave: &mut Cave,
     spawn_position: Position<usize>,
     floor_y: usize,
 ) -> usize {
-    let mut count = 1;
-    grid[spawn_position] = Tile::Sand;
-    let mid = spawn_position.x;
+    let mut sand = BitGrid::new(cave.rows(), cave.columns());
+    sand.insert(spawn_position);
 
+    // The pyramid never touches the sides of the cave, so no sand is lost
+    // when shifting the rows
     for row in (spawn_position.y + 1)..floor_y {
-        let width: usize = (row + 1) * 2 - 1;
-        let offset: usize = width / 2;
-        for col in (mid - offset)..=(mid + offset) {
-            match (
-                grid.get(col - 1, row - 1),
-                grid.get(col, row - 1),
-                grid.get(col + 1, row - 1),
-            ) {
-                (Some(Tile::Sand), ..)
-                | (_, Some(Tile::Sand), _)
-                | (_, _, Some(Tile::Sand)) => {
-                    if grid[row][col] == Tile::Air {
-                        grid[row][col] = Tile::Sand;
-                        count += 1;
-                    }
-                }
-                _ => (),
-            }
-        }
+        let above = sand.row(row - 1).to_vec();
+        sand.or_row(row, &above);
+        sand.shift_row_right(row, 1);
+        sand.or_row(row, &above);
+        let half = sand.row(row).to_vec();
+        sand.shift_row_left(row, 1);
+        sand.or_row(row, &half);
+        sand.and_not_row(row, cave.rock.row(row));
+    }
+    for pos in sand.iter_ones() {
+        cave.block(pos);
     }
-    count
+
+    sand.count_ones()
 }
 
 pub fn task2(input: &str) -> SolutionResult {
-    let mut grid: Grid<Tile> = parse(input);
+    let mut cave = parse(input);
 
-    let max_y = grid.iter_rows().enumerate().fold(0, |max_y, (i, row)| {
-        if row.contains(&Tile::Wall) {
-            i
-        } else {
-            max_y
-        }
-    });
+    let max_y = (0..cave.rows())
+        .rev()
+        .find(|&y| cave.rock.row_count_ones(y) > 0)
+        .unwrap_or(0);
 
-    if grid.columns() <= ((max_y + 2) * 2 - 1) {
-        panic!("Not enough columns for correct simulation of part2, current columns are {} but need {}", grid.columns(), ((max_y+2)*2 - 1))
+    if cave.columns() <= ((max_y + 2) * 2 - 1) {
+        panic!("Not enough columns for correct simulation of part2, current columns are {} but need {}", cave.columns(), ((max_y+2)*2 - 1))
     }
 
     // Unnecessary, uncomment if you wanna draw floor
-    //let floor = &mut grid[max_y + 2];
-    //floor.fill(Tile::Wall);
+    //for x in 0..cave.columns() {
+    //    cave.add_rock(x, max_y + 2);
+    //}
 
     let spawn_position = Position::new(500, 0) - Position::new(MIN_X, MIN_Y);
 
-    let counter = fill_pyramid(&mut grid, spawn_position, max_y + 2);
+    let counter = fill_pyramid(&mut cave, spawn_position, max_y + 2);
 
-    //draw_grid(&grid);
+    if verbose() {
+        draw_cave(&cave);
+    }
 
     SolutionResult::Unsigned(counter)
 }
@@ -0,0 +1,223 @@
+//! The simulation on a `Grid<Tile>`, one tile at a time, from before the
+//! [`Cave`](super::Cave) bit grids. Kept to compare them with `--bench`.
+
+use std::cmp::Ordering;
+
+use aoc_lib::{
+    structs::{grid::Grid, position::Position},
+    tooling::SolutionResult,
+};
+
+use super::{Tile, MAX_X, MAX_Y, MIN_X, MIN_Y};
+
+/// Spawns a grain of sand and modifies the grid changing an Air tile with a
+/// Sand tile in its final resting position, and returns said Position. If the
+/// Sand flows off to the abyss then the grid is left untouched and the position
+/// will have the last position of the sand before it left the grid.
+fn spawn_sand(
+    grid: &mut Grid<Tile>,
+    spawn_position: Position<usize>,
+) -> Position<usize> {
+    if spawn_position.x >= grid.columns() || spawn_position.y >= grid.rows() {
+        panic!("Spawn position out of bounds, the dimensions are {:?} but the spawn is {spawn_position}", (grid.columns(), grid.rows()) );
+    }
+    if grid[spawn_position] != Tile::Air {
+        panic!("Attempted to spawn sand at a clogged spawn position");
+    }
+
+    let mut current_pos = spawn_position;
+
+    loop {
+        let down_left = grid.get(
+            current_pos
+                .x
+                .checked_sub(1)
+                .unwrap_or_else(|| grid.columns()),
+            current_pos.y + 1,
+        );
+        let down_center = grid.get(current_pos.x, current_pos.y + 1);
+        let down_right = grid.get(current_pos.x + 1, current_pos.y + 1);
+
+        match (down_left, down_center, down_right) {
+            // Move down straight
+            (_, Some(Tile::Air), _) => {
+                while let Some(Tile::Air) =
+                    grid.get(current_pos.x, current_pos.y + 1)
+                {
+                    current_pos.y += 1;
+                }
+            }
+            // Move down left
+            (Some(Tile::Air), Some(Tile::Rock | Tile::Sand), _) => {
+                current_pos.x -= 1;
+                current_pos.y += 1;
+            }
+            // Move down right
+            (
+                Some(Tile::Rock | Tile::Sand),
+                Some(Tile::Rock | Tile::Sand),
+                Some(Tile::Air),
+            ) => {
+                current_pos.x += 1;
+                current_pos.y += 1;
+            }
+            // Blocked, stop
+            (
+                Some(Tile::Rock | Tile::Sand),
+                Some(Tile::Rock | Tile::Sand),
+                Some(Tile::Rock | Tile::Sand),
+            ) => {
+                grid[current_pos] = Tile::Sand;
+                break;
+            }
+            // Reached bottom, stop
+            (_, None, _) => {
+                break;
+            }
+            // Overflow to left, stop at bottom left corner
+            (None, Some(Tile::Rock | Tile::Sand), _) => {
+                current_pos = Position::new(0, grid.rows() - 1);
+                break;
+            }
+            // Overflow to right, stop at bottom right corner
+            (
+                Some(Tile::Rock | Tile::Sand),
+                Some(Tile::Rock | Tile::Sand),
+                None,
+            ) => {
+                current_pos =
+                    Position::new(grid.columns() - 1, grid.rows() - 1);
+                break;
+            }
+        }
+    }
+
+    current_pos
+}
+
+fn parse(input: &str) -> Grid<Tile> {
+    let coords_iter = input.lines().map(|l| {
+        l.split(" -> ")
+            .map(|coords| {
+                let coords = coords.split_once(',').unwrap();
+                Position::new(
+                    coords.0.parse::<usize>().unwrap() - MIN_X,
+                    coords.1.parse::<usize>().unwrap() - MIN_Y,
+                )
+            })
+            .peekable()
+    });
+
+    let mut grid = Grid::with_val(Tile::Air, MAX_Y - MIN_Y, MAX_X - MIN_X);
+
+    for mut wall in coords_iter {
+        let mut current = wall.next().unwrap();
+        while let Some(next) = wall.peek() {
+            match (next.x.cmp(&current.x), next.y.cmp(&current.y)) {
+                (Ordering::Greater, Ordering::Equal) => {
+                    for x in current.x..=next.x {
+                        grid[current.y][x] = Tile::Rock;
+                    }
+                }
+                (Ordering::Equal, Ordering::Greater) => {
+                    for y in current.y..=next.y {
+                        grid[y][current.x] = Tile::Rock;
+                    }
+                }
+                (Ordering::Less, Ordering::Equal) => {
+                    for x in next.x..=current.x {
+                        grid[current.y][x] = Tile::Rock;
+                    }
+                }
+                (Ordering::Equal, Ordering::Less) => {
+                    for y in next.y..=current.y {
+                        grid[y][current.x] = Tile::Rock;
+                    }
+                }
+                (Ordering::Equal, Ordering::Equal) => {
+                    panic!("false assumption (non-repeat vertices)")
+                }
+                (
+                    Ordering::Greater | Ordering::Less,
+                    Ordering::Greater | Ordering::Less,
+                ) => {
+                    panic!("false assumption (straight walls)")
+                }
+            }
+            current = wall.next().unwrap();
+        }
+    }
+
+    grid
+}
+
+pub fn task1(input: &str) -> SolutionResult {
+    let mut grid: Grid<Tile> = parse(input);
+
+    let spawn_position = Position::new(500, 0) - Position::new(MIN_X, MIN_Y);
+
+    let mut counter = 0;
+    let mut resting_position = spawn_sand(&mut grid, spawn_position);
+
+    while resting_position.y + 1 < MAX_Y {
+        counter += 1;
+        resting_position = spawn_sand(&mut grid, spawn_position);
+    }
+
+    SolutionResult::Unsigned(counter)
+}
+
+fn fill_pyramid(
+    grid: &mut Grid<Tile>,
+    spawn_position: Position<usize>,
+    floor_y: usize,
+) -> usize {
+    let mut count = 1;
+    grid[spawn_position] = Tile::Sand;
+    let mid = spawn_position.x;
+
+    for row in (spawn_position.y + 1)..floor_y {
+        let width: usize = (row + 1) * 2 - 1;
+        let offset: usize = width / 2;
+        for col in (mid - offset)..=(mid + offset) {
+            match (
+                grid.get(col - 1, row - 1),
+                grid.get(col, row - 1),
+                grid.get(col + 1, row - 1),
+            ) {
+                (Some(Tile::Sand), ..)
+                | (_, Some(Tile::Sand), _)
+                | (_, _, Some(Tile::Sand))
+                    if grid[row][col] == Tile::Air =>
+                {
+                    grid[row][col] = Tile::Sand;
+                    count += 1;
+                }
+                _ => (),
+            }
+        }
+    }
+    count
+}
+
+pub fn task2(input: &str) -> SolutionResult {
+    let mut grid: Grid<Tile> = parse(input);
+
+    let max_y = grid.iter_rows().enumerate().fold(0, |max_y, (i, row)| {
+        if row.contains(&Tile::Rock) {
+            i
+        } else {
+            max_y
+        }
+    });
+
+    if grid.columns() <= ((max_y + 2) * 2 - 1) {
+        panic!("Not enough columns for correct simulation of part2, current columns are {} but need {}", grid.columns(), ((max_y+2)*2 - 1))
+    }
+
+    let spawn_position = Position::new(500, 0) - Position::new(MIN_X, MIN_Y);
+
+    let counter = fill_pyramid(&mut grid, spawn_position, max_y + 2);
+
+    SolutionResult::Unsigned(counter)
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use aoc_lib::{
+    expr::{Expr, Operator},
+    tooling::SolutionResult,
+};
+
+type Num = i64;
+
+const ROOT: &str = "root";
+const HUMN: &str = "humn";
+
+/// Every monkey's job is either a constant or a binary operation over the
+/// values yelled by two other monkeys (`Expr::Var` holds their names).
+type Monkeys<'a> = HashMap<&'a str, Expr<Num, &'a str>>;
+
+fn parse(input: &str) -> Monkeys<'_> {
+    input
+        .lines()
+        .map(|l| {
+            let (name, job) = l.split_once(": ").unwrap();
+            let job = match job.split_ascii_whitespace().collect::<Vec<_>>()[..]
+            {
+                [n] => Expr::Const(n.parse().unwrap()),
+                [lhs, op, rhs] => Expr::bin_op(
+                    op.parse().unwrap(),
+                    Expr::Var(lhs),
+                    Expr::Var(rhs),
+                ),
+                _ => panic!("Unexpected monkey job: '{job}'"),
+            };
+            (name, job)
+        })
+        .collect()
+}
+
+fn value(monkeys: &Monkeys, name: &str) -> Num {
+    monkeys[name].eval(&|&other| value(monkeys, other))
+}
+
+fn operands<'a>(
+    monkeys: &Monkeys<'a>,
+    name: &str,
+) -> (Operator, &'a str, &'a str) {
+    match &monkeys[name] {
+        Expr::BinOp(op, lhs, rhs) => match (lhs.as_ref(), rhs.as_ref()) {
+            (Expr::Var(lhs), Expr::Var(rhs)) => (*op, lhs, rhs),
+            _ => panic!("Monkey '{name}' doesn't operate over other monkeys"),
+        },
+        _ => panic!("Monkey '{name}' doesn't do an operation"),
+    }
+}
+
+/// Names of all the monkeys whose value depends on `humn`'s (`humn` included).
+/// Assumes every monkey is listened to by at most one other monkey, so the
+/// expression DAG is actually a tree and the path is unique.
+fn humn_path<'a>(monkeys: &Monkeys<'a>) -> Vec<&'a str> {
+    let parents: HashMap<&str, &str> = monkeys
+        .iter()
+        .flat_map(|(&name, job)| match job {
+            Expr::BinOp(_, lhs, rhs) => [lhs, rhs]
+                .into_iter()
+                .filter_map(|e| match e.as_ref() {
+                    Expr::Var(v) => Some((*v, name)),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        })
+        .collect();
+
+    let mut path = vec![HUMN];
+    while let Some(&parent) = parents.get(path.last().unwrap()) {
+        path.push(parent);
+    }
+    path
+}
+
+pub fn task1(input: &str) -> SolutionResult {
+    let monkeys = parse(input);
+
+    SolutionResult::Unsigned(value(&monkeys, ROOT).try_into().unwrap())
+}
+
+pub fn task2(input: &str) -> SolutionResult {
+    let monkeys = parse(input);
+    let mut path = humn_path(&monkeys);
+    path.reverse();
+
+    // `target` is the value the current monkey must yell for root to match,
+    // walk down from root inverting each operation on the branch with humn.
+    let (_, lhs, rhs) = operands(&monkeys, ROOT);
+    let mut target = if path[1] == lhs {
+        value(&monkeys, rhs)
+    } else {
+        value(&monkeys, lhs)
+    };
+
+    for pair in path[1..].windows(2) {
+        let (op, lhs, rhs) = operands(&monkeys, pair[0]);
+        target = if pair[1] == lhs {
+            op.solve_lhs(target, value(&monkeys, rhs))
+        } else {
+            op.solve_rhs(target, value(&monkeys, lhs))
+        };
+    }
+
+    SolutionResult::Unsigned(target.try_into().unwrap())
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use aoc_lib::{
+    structs::{grid::Grid, position::Position},
+    tooling::SolutionResult,
+};
+
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+enum Tile {
+    #[default]
+    Void,
+    Open,
+    Wall,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Forward(usize),
+    Left,
+    Right,
+}
+
+/// Ordered clockwise so that the discriminant is also the facing's password
+/// value.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+enum Facing {
+    Right = 0,
+    Down = 1,
+    Left = 2,
+    Up = 3,
+}
+
+const FACINGS: [Facing; 4] =
+    [Facing::Right, Facing::Down, Facing::Left, Facing::Up];
+
+impl Facing {
+    fn turn(self, step: Step) -> Facing {
+        match step {
+            Step::Right => FACINGS[(self as usize + 1) % 4],
+            Step::Left => FACINGS[(self as usize + 3) % 4],
+            Step::Forward(_) => self,
+        }
+    }
+
+    fn opposite(self) -> Facing { FACINGS[(self as usize + 2) % 4] }
+}
+
+fn parse(input: &str) -> (Grid<Tile>, Vec<Step>) {
+    let (map, path) = input.split_once("\n\n").unwrap();
+
+    let columns = map.lines().map(|l| l.len()).max().unwrap();
+    let mut tiles: Vec<Tile> =
+        Vec::with_capacity(columns * map.lines().count());
+    for line in map.lines() {
+        tiles.extend(line.chars().map(|c| match c {
+            ' ' => Tile::Void,
+            '.' => Tile::Open,
+            '#' => Tile::Wall,
+            _ => panic!("Unexpected char in map: '{c}'"),
+        }));
+        tiles.extend((line.len()..columns).map(|_| Tile::Void));
+    }
+    let grid = Grid::from_vec(tiles, columns).unwrap();
+
+    let mut steps = Vec::new();
+    let mut distance = 0;
+    for c in path.trim().chars() {
+        match c {
+            'L' | 'R' => {
+                steps.push(Step::Forward(distance));
+                steps.push(if c == 'L' { Step::Left } else { Step::Right });
+                distance = 0;
+            }
+            d => distance = distance * 10 + d.to_digit(10).unwrap() as usize,
+        }
+    }
+    steps.push(Step::Forward(distance));
+
+    (grid, steps)
+}
+
+/// Next position in the grid walking towards `facing` if it isn't off the map
+fn next_on_map(
+    grid: &Grid<Tile>,
+    pos: Position<usize>,
+    facing: Facing,
+) -> Option<Position<usize>> {
+    let next = match facing {
+        Facing::Right => Position::new(pos.x + 1, pos.y),
+        Facing::Down => Position::new(pos.x, pos.y + 1),
+        Facing::Left => Position::new(pos.x.checked_sub(1)?, pos.y),
+        Facing::Up => Position::new(pos.x, pos.y.checked_sub(1)?),
+    };
+    if next.x >= grid.columns() {
+        return None;
+    }
+    match grid.get(next.x, next.y) {
+        Some(Tile::Open | Tile::Wall) => Some(next),
+        _ => None,
+    }
+}
+
+/// Follows the path and returns the final password. `wrap` is called whenever
+/// a step goes off the map and has to give the position and facing on the
+/// other side.
+fn walk(
+    grid: &Grid<Tile>,
+    steps: &[Step],
+    wrap: impl Fn(Position<usize>, Facing) -> (Position<usize>, Facing),
+) -> usize {
+    let start_x = grid[0].iter().position(|&t| t == Tile::Open).unwrap();
+    let mut pos = Position::new(start_x, 0);
+    let mut facing = Facing::Right;
+
+    for &step in steps {
+        let Step::Forward(distance) = step else {
+            facing = facing.turn(step);
+            continue;
+        };
+
+        for _ in 0..distance {
+            let (next, next_facing) = match next_on_map(grid, pos, facing) {
+                Some(next) => (next, facing),
+                None => wrap(pos, facing),
+            };
+            if grid[next] == Tile::Wall {
+                break;
+            }
+            pos = next;
+            facing = next_facing;
+        }
+    }
+
+    1000 * (pos.y + 1) + 4 * (pos.x + 1) + facing as usize
+}
+
+pub fn task1(input: &str) -> SolutionResult {
+    let (grid, steps) = parse(input);
+
+    let wrap = |pos: Position<usize>, facing: Facing| {
+        let mut back = pos;
+        while let Some(prev) = next_on_map(&grid, back, facing.opposite()) {
+            back = prev;
+        }
+        (back, facing)
+    };
+
+    SolutionResult::Unsigned(walk(&grid, &steps, wrap))
+}
+
+type Vec3 = [i32; 3];
+
+fn neg(v: Vec3) -> Vec3 { [-v[0], -v[1], -v[2]] }
+
+/// Orientation of a face of the net once it's folded into the cube: the
+/// outward normal and the 3D directions that the net's right and down map to.
+#[derive(Debug, Clone, Copy)]
+struct FaceOrientation {
+    normal: Vec3,
+    right: Vec3,
+    down: Vec3,
+}
+
+impl FaceOrientation {
+    fn towards(&self, facing: Facing) -> Vec3 {
+        match facing {
+            Facing::Right => self.right,
+            Facing::Down => self.down,
+            Facing::Left => neg(self.right),
+            Facing::Up => neg(self.down),
+        }
+    }
+
+    /// Orientation of the neighboring face in the net after folding the edge
+    /// towards `facing` by 90 degrees.
+    fn fold(&self, facing: Facing) -> FaceOrientation {
+        let normal = self.towards(facing);
+        let onwards = neg(self.normal);
+        match facing {
+            Facing::Right => FaceOrientation {
+                normal,
+                right: onwards,
+                ..*self
+            },
+            Facing::Left => FaceOrientation {
+                normal,
+                right: neg(onwards),
+                ..*self
+            },
+            Facing::Down => FaceOrientation {
+                normal,
+                down: onwards,
+                ..*self
+            },
+            Facing::Up => FaceOrientation {
+                normal,
+                down: neg(onwards),
+                ..*self
+            },
+        }
+    }
+}
+
+/// Net folded into a cube, faces are indexed by their position in the net
+/// measured in face lengths.
+struct Cube {
+    size: usize,
+    faces: HashMap<Position<usize>, FaceOrientation>,
+}
+
+impl Cube {
+    /// Folds the net by walking it from the first face and rolling the cube
+    /// over each edge shared between neighboring faces of the net, so any of
+    /// the 11 possible nets works.
+    fn fold(grid: &Grid<Tile>) -> Cube {
+        let area = grid.iter_all().filter(|&&t| t != Tile::Void).count();
+        let size = (1..).find(|s| s * s * 6 >= area).unwrap();
+
+        let is_face = |face: Position<usize>| {
+            face.x * size < grid.columns()
+                && matches!(
+                    grid.get(face.x * size, face.y * size),
+                    Some(Tile::Open | Tile::Wall)
+                )
+        };
+
+        let first = (0..).map(|x| Position::new(x, 0)).find(|&f| is_face(f));
+        let first = first.unwrap();
+        let mut faces = HashMap::from([(
+            first,
+            FaceOrientation {
+                normal: [0, 0, -1],
+                right: [1, 0, 0],
+                down: [0, 1, 0],
+            },
+        )]);
+
+        let mut stack = vec![first];
+        while let Some(face) = stack.pop() {
+            let orientation = faces[&face];
+            for facing in FACINGS {
+                let neighbor = match facing {
+                    Facing::Right => Some(Position::new(face.x + 1, face.y)),
+                    Facing::Down => Some(Position::new(face.x, face.y + 1)),
+                    Facing::Left => {
+                        face.x.checked_sub(1).map(|x| Position::new(x, face.y))
+                    }
+                    Facing::Up => {
+                        face.y.checked_sub(1).map(|y| Position::new(face.x, y))
+                    }
+                };
+                if let Some(neighbor) = neighbor {
+                    if is_face(neighbor) && !faces.contains_key(&neighbor) {
+                        faces.insert(neighbor, orientation.fold(facing));
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+
+        if faces.len() != 6 {
+            panic!("The map isn't a cube net, found {} faces", faces.len());
+        }
+
+        Cube { size, faces }
+    }
+
+    /// Offset of a local position along the edge towards `facing`, measured
+    /// clockwise around the face.
+    fn edge_offset(&self, local: Position<usize>, facing: Facing) -> usize {
+        let last = self.size - 1;
+        match facing {
+            Facing::Up => local.x,
+            Facing::Right => local.y,
+            Facing::Down => last - local.x,
+            Facing::Left => last - local.y,
+        }
+    }
+
+    fn wrap(
+        &self,
+        pos: Position<usize>,
+        facing: Facing,
+    ) -> (Position<usize>, Facing) {
+        let face = Position::new(pos.x / self.size, pos.y / self.size);
+        let local = Position::new(pos.x % self.size, pos.y % self.size);
+        let from = self.faces[&face];
+
+        let towards = from.towards(facing);
+        let (&to_face, to) = self
+            .faces
+            .iter()
+            .find(|(_, orientation)| orientation.normal == towards)
+            .unwrap();
+        let edge = FACINGS
+            .into_iter()
+            .find(|&f| to.towards(f) == from.normal)
+            .unwrap();
+
+        // Both faces go clockwise around their shared edge in opposite ways
+        let last = self.size - 1;
+        let offset = last - self.edge_offset(local, facing);
+        let local = match edge {
+            Facing::Up => Position::new(offset, 0),
+            Facing::Right => Position::new(last, offset),
+            Facing::Down => Position::new(last - offset, last),
+            Facing::Left => Position::new(0, last - offset),
+        };
+
+        (
+            Position::new(
+                to_face.x * self.size + local.x,
+                to_face.y * self.size + local.y,
+            ),
+            edge.opposite(),
+        )
+    }
+}
+
+pub fn task2(input: &str) -> SolutionResult {
+    let (grid, steps) = parse(input);
+    let cube = Cube::fold(&grid);
+
+    SolutionResult::Unsigned(walk(&grid, &steps, |pos, facing| {
+        cube.wrap(pos, facing)
+    }))
+}
@@ -0,0 +1,159 @@
+use aoc_lib::{
+    structs::{grid::Grid, position::Position},
+    tooling::SolutionResult,
+};
+
+/// Free space kept around the elves whenever the grid has to grow.
+const MARGIN: usize = 16;
+
+const NEIGHBORS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Proposal directions in their initial priority: N, S, W, E. Each one holds
+/// the move itself followed by the two diagonals that also have to be free.
+const PROPOSALS: [[(isize, isize); 3]; 4] = [
+    [(0, -1), (-1, -1), (1, -1)],
+    [(0, 1), (-1, 1), (1, 1)],
+    [(-1, 0), (-1, -1), (-1, 1)],
+    [(1, 0), (1, -1), (1, 1)],
+];
+
+fn offset(pos: Position<usize>, (dx, dy): (isize, isize)) -> Position<usize> {
+    Position::new(pos.x.wrapping_add_signed(dx), pos.y.wrapping_add_signed(dy))
+}
+
+/// Elves on a dense grid that grows whenever they spread close to its edges,
+/// so neighbor lookups never have to check bounds.
+struct Elves {
+    occupied: Grid<bool>,
+    proposals: Grid<u8>,
+    elves: Vec<Position<usize>>,
+    round: usize,
+}
+
+impl Elves {
+    fn parse(input: &str) -> Elves {
+        let elves: Vec<Position<usize>> = input
+            .lines()
+            .enumerate()
+            .flat_map(|(y, l)| {
+                l.char_indices()
+                    .filter(|&(_, c)| c == '#')
+                    .map(move |(x, _)| Position::new(x, y))
+            })
+            .collect();
+
+        let mut elves = Elves {
+            occupied: Grid::new(0, 0),
+            proposals: Grid::new(0, 0),
+            elves,
+            round: 0,
+        };
+        elves.regrow();
+        elves
+    }
+
+    /// Returns the (min, max) corners of the smallest rectangle containing
+    /// every elf.
+    fn bounds(&self) -> (Position<usize>, Position<usize>) {
+        self.elves.iter().fold(
+            (Position::new(usize::MAX, usize::MAX), Position::new(0, 0)),
+            |(min, max), e| {
+                (
+                    Position::new(min.x.min(e.x), min.y.min(e.y)),
+                    Position::new(max.x.max(e.x), max.y.max(e.y)),
+                )
+            },
+        )
+    }
+
+    /// Rebuilds the grids so that there's `MARGIN` free tiles around the elves
+    fn regrow(&mut self) {
+        let (min, max) = self.bounds();
+        let rows = max.y - min.y + 1 + 2 * MARGIN;
+        let columns = max.x - min.x + 1 + 2 * MARGIN;
+
+        self.occupied = Grid::new(rows, columns);
+        self.proposals = Grid::new(rows, columns);
+        for elf in &mut self.elves {
+            *elf = *elf - min + Position::new(MARGIN, MARGIN);
+            self.occupied[*elf] = true;
+        }
+    }
+
+    fn proposal(&self, elf: Position<usize>) -> Option<Position<usize>> {
+        let free = |delta: (isize, isize)| !self.occupied[offset(elf, delta)];
+
+        if NEIGHBORS.into_iter().all(free) {
+            return None;
+        }
+        (0..4)
+            .map(|i| PROPOSALS[(self.round + i) % 4])
+            .find(|checks| checks.iter().all(|&delta| free(delta)))
+            .map(|checks| offset(elf, checks[0]))
+    }
+
+    /// Runs a whole round and returns whether any elf moved
+    fn step(&mut self) -> bool {
+        let proposals: Vec<Option<Position<usize>>> =
+            self.elves.iter().map(|&elf| self.proposal(elf)).collect();
+
+        for &target in proposals.iter().flatten() {
+            self.proposals[target] += 1;
+        }
+
+        let mut moved = false;
+        let mut near_edge = false;
+        for (elf, target) in self.elves.iter_mut().zip(&proposals) {
+            let Some(target) = *target else {
+                continue;
+            };
+            if self.proposals[target] == 1 {
+                self.occupied[*elf] = false;
+                self.occupied[target] = true;
+                *elf = target;
+                moved = true;
+                near_edge |= target.x == 0
+                    || target.y == 0
+                    || target.x == self.occupied.columns() - 1
+                    || target.y == self.occupied.rows() - 1;
+            }
+            self.proposals[target] = 0;
+        }
+
+        self.round += 1;
+        if near_edge {
+            self.regrow();
+        }
+        moved
+    }
+}
+
+pub fn task1(input: &str) -> SolutionResult {
+    let mut elves = Elves::parse(input);
+
+    for _ in 0..10 {
+        elves.step();
+    }
+
+    let (min, max) = elves.bounds();
+    let area = (max.x - min.x + 1) * (max.y - min.y + 1);
+
+    SolutionResult::Unsigned(area - elves.elves.len())
+}
+
+pub fn task2(input: &str) -> SolutionResult {
+    let mut elves = Elves::parse(input);
+
+    while elves.step() {}
+
+    SolutionResult::Unsigned(elves.round)
+}
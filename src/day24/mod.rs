@@ -0,0 +1,141 @@
+use aoc_lib::{
+    math::lcm, search::bfs, structs::position::Position,
+    tooling::SolutionResult,
+};
+
+type Bits = u128;
+
+/// The blizzards of the valley, split by direction into one bitset per row
+/// (for horizontal ones) or per column (for vertical ones) with their initial
+/// positions. Since they wrap around, at minute `t` each set is just rotated
+/// `t` tiles in its direction.
+struct Valley {
+    width: usize,
+    height: usize,
+    left: Vec<Bits>,
+    right: Vec<Bits>,
+    up: Vec<Bits>,
+    down: Vec<Bits>,
+    /// Minutes after which every blizzard is back on its initial position
+    period: usize,
+}
+
+/// A position in the valley at a given minute (modulo the valley's period).
+/// Positions are measured including the walls, so the entrance is at (1, 0).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct State {
+    pos: Position<usize>,
+    time: usize,
+}
+
+impl Valley {
+    fn parse(input: &str) -> Valley {
+        let lines: Vec<&str> = input.lines().collect();
+        let width = lines[0].len() - 2;
+        let height = lines.len() - 2;
+        if width > Bits::BITS as usize || height > Bits::BITS as usize {
+            panic!("Valley of {width}x{height} doesn't fit in the bitsets");
+        }
+
+        let mut valley = Valley {
+            width,
+            height,
+            left: vec![0; height],
+            right: vec![0; height],
+            up: vec![0; width],
+            down: vec![0; width],
+            period: lcm(width, height),
+        };
+
+        for (y, line) in lines[1..=height].iter().enumerate() {
+            for (x, c) in line[1..=width].chars().enumerate() {
+                match c {
+                    '<' => valley.left[y] |= 1 << x,
+                    '>' => valley.right[y] |= 1 << x,
+                    '^' => valley.up[x] |= 1 << y,
+                    'v' => valley.down[x] |= 1 << y,
+                    '.' => (),
+                    _ => panic!("Unexpected char in valley: '{c}'"),
+                }
+            }
+        }
+
+        valley
+    }
+
+    fn entrance(&self) -> Position<usize> { Position::new(1, 0) }
+
+    fn exit(&self) -> Position<usize> {
+        Position::new(self.width, self.height + 1)
+    }
+
+    fn is_free(&self, pos: Position<usize>, time: usize) -> bool {
+        if pos == self.entrance() || pos == self.exit() {
+            return true;
+        }
+        if pos.x == 0 || pos.x > self.width || pos.y == 0 || pos.y > self.height
+        {
+            return false;
+        }
+
+        let (x, y) = (pos.x - 1, pos.y - 1);
+        let (w, h) = (self.width, self.height);
+        let blizzards = (self.left[y] >> ((x + time) % w))
+            | (self.right[y] >> ((x + w - time % w) % w))
+            | (self.up[x] >> ((y + time) % h))
+            | (self.down[x] >> ((y + h - time % h) % h));
+
+        blizzards & 1 == 0
+    }
+
+    fn next_states(&self, state: &State) -> impl Iterator<Item = State> + '_ {
+        let State { pos, time } = *state;
+        let time = (time + 1) % self.period;
+
+        [
+            Some(pos),
+            Some(Position::new(pos.x + 1, pos.y)),
+            Some(Position::new(pos.x, pos.y + 1)),
+            pos.x.checked_sub(1).map(|x| Position::new(x, pos.y)),
+            pos.y.checked_sub(1).map(|y| Position::new(pos.x, y)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(move |&p| self.is_free(p, time))
+        .map(move |pos| State { pos, time })
+    }
+
+    /// Minutes needed to cross from `from` to `to` starting at minute `start`
+    fn cross(
+        &self,
+        from: Position<usize>,
+        to: Position<usize>,
+        start: usize,
+    ) -> usize {
+        let start = State {
+            pos: from,
+            time: start % self.period,
+        };
+        let path = bfs(start, |s| self.next_states(s), |s| s.pos == to)
+            .expect("The valley can't be crossed");
+
+        path.len() - 1
+    }
+}
+
+pub fn task1(input: &str) -> SolutionResult {
+    let valley = Valley::parse(input);
+
+    SolutionResult::Unsigned(valley.cross(valley.entrance(), valley.exit(), 0))
+}
+
+pub fn task2(input: &str) -> SolutionResult {
+    let valley = Valley::parse(input);
+    let (entrance, exit) = (valley.entrance(), valley.exit());
+
+    let there = valley.cross(entrance, exit, 0);
+    let back = valley.cross(exit, entrance, there);
+    let there_again = valley.cross(entrance, exit, there + back);
+
+    SolutionResult::Unsigned(there + back + there_again)
+}
@@ -0,0 +1,12 @@
+use aoc_lib::{structs::snafu::Snafu, tooling::SolutionResult};
+
+pub fn task1(input: &str) -> SolutionResult {
+    let sum: Snafu = input.lines().map(|l| l.parse::<Snafu>().unwrap()).sum();
+
+    SolutionResult::Str(sum.to_string())
+}
+
+/// Day 25 has no second puzzle, it's given for having every other star
+pub fn task2(_input: &str) -> SolutionResult {
+    SolutionResult::Str("Start the blender!".to_string())
+}
@@ -16,7 +16,6 @@ pub fn task1(input: &str) -> SolutionResult {
     SolutionResult::Signed(input.lines().fold(0, |acc, line| {
         let length = line.chars().count();
         let repeat_char = find_repeat(&line[..length / 2], &line[length / 2..]);
-        //println!("Repeat char found in line {}: '{}'\tValue:{}",line,repeat_char, get_priority(repeat_char));
         acc + get_priority(repeat_char)
     }))
 }
@@ -33,7 +32,6 @@ pub fn task2(input: &str) -> SolutionResult {
         0,
         |acc, ref lines| {
             let repeat_char = find_badge(lines);
-            //println!( "Repeat char found in lines {:?}: '{}'\tValue:{}", &lines, repeat_char, get_priority(repeat_char));
             acc + get_priority(repeat_char)
         },
     ))
@@ -1,236 +1,308 @@
-use std::{
-    cell::RefCell,
-    cmp::min,
-    iter::Peekable,
-    rc::{Rc, Weak},
-};
+use std::cell::Cell;
+
+use thiserror::Error;
 
 use aoc_lib::tooling::SolutionResult;
 
-type RcCell<T> = Rc<RefCell<T>>;
-type WeakCell<T> = Weak<RefCell<T>>;
+pub mod shell;
 
 const MAX_SIZE: usize = 100000;
 const SPACE_AVAILABLE: usize = 70000000;
 const SPACE_NEEDED: usize = 30000000;
 
+/// Index of a file or directory in its [`FsTree`]
+type NodeId = usize;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+enum FsError {
+    #[error("no directory '{name}' in '{path}'")]
+    NoSuchDir { name: String, path: String },
+    #[error("'/' has no parent directory")]
+    NoParent,
+    #[error("'{0}' was already listed")]
+    RepeatedLs(String),
+    #[error("unknown command '{0}'")]
+    UnknownCommand(String),
+    #[error("output line '{0}' isn't part of an ls")]
+    UnexpectedOutput(String),
+    #[error("invalid ls entry '{0}'")]
+    InvalidEntry(String),
+    #[error("no such file or directory '{0}'")]
+    NotFound(String),
+    #[error("'{0}' already exists")]
+    AlreadyExists(String),
+    #[error("'{0}' is not a directory")]
+    NotADir(String),
+    #[error("the root directory can't be removed")]
+    RemoveRoot,
+}
+
 #[derive(Debug)]
-enum File<'a> {
-    Data {
-        name: &'a str,
-        data: usize,
-    },
-    Dir {
-        name: &'a str,
-        files: Vec<RcCell<File<'a>>>,
-        parent: WeakCell<File<'a>>,
-        size: Option<usize>, // sizes have to be initialized
-    },
-    Root {
-        files: Vec<RcCell<File<'a>>>,
-        size: Option<usize>,
-    },
+enum Kind {
+    File { size: usize },
+    Dir { children: Vec<NodeId>, listed: bool },
 }
 
-impl<'a> File<'a> {
-    fn new_dir(parent: RcCell<File<'a>>, name: &'a str) -> RcCell<File<'a>> {
-        Rc::new(RefCell::new(File::Dir {
-            name,
-            files: Vec::with_capacity(32),
-            parent: Rc::downgrade(&parent),
-            size: None,
-        }))
-    }
+#[derive(Debug)]
+struct Node {
+    name: String,
+    parent: Option<NodeId>,
+    kind: Kind,
+    /// Total size, computed the first time it's asked for
+    size: Cell<Option<usize>>,
+}
 
-    fn new_data(data: usize, name: &'a str) -> RcCell<File<'a>> {
-        Rc::new(RefCell::new(File::Data { name, data }))
-    }
+/// Filesystem reconstructed from the terminal output, every file and
+/// directory lives in one arena and refers to the others by index.
+#[derive(Debug)]
+struct FsTree {
+    nodes: Vec<Node>,
+}
 
-    fn get_name(&self) -> &str {
-        match self {
-            File::Data { name, .. } => name,
-            File::Dir { name, .. } => name,
-            File::Root { .. } => "/",
+impl FsTree {
+    const ROOT: NodeId = 0;
+
+    /// Filesystem with only the root directory
+    fn new() -> FsTree {
+        FsTree {
+            nodes: vec![Node {
+                name: String::new(),
+                parent: None,
+                kind: Kind::Dir {
+                    children: Vec::new(),
+                    listed: false,
+                },
+                size: Cell::new(None),
+            }],
         }
     }
 
-    fn get_parent(&self) -> RcCell<File<'a>> {
-        match self {
-            File::Dir { parent, .. } => parent.upgrade().unwrap(),
-            _ => panic!("Called get_parent on non-dir file"),
+    /// Replays the commands of a terminal session
+    fn from_transcript(transcript: &str) -> Result<FsTree, FsError> {
+        let mut tree = FsTree::new();
+        let mut cwd = FsTree::ROOT;
+        let mut lines = transcript.lines().peekable();
+
+        while let Some(line) = lines.next() {
+            let command = line
+                .strip_prefix("$ ")
+                .ok_or_else(|| FsError::UnexpectedOutput(line.to_string()))?;
+            match command.split_once(' ') {
+                Some(("cd", dir)) => cwd = tree.cd(cwd, dir)?,
+                None if command == "ls" => {
+                    let output = std::iter::from_fn(|| {
+                        lines.next_if(|l| !l.starts_with('$'))
+                    });
+                    tree.ls(cwd, output)?;
+                }
+                _ => return Err(FsError::UnknownCommand(command.to_string())),
+            }
         }
+
+        Ok(tree)
     }
 
-    fn get_files(&self) -> &Vec<RcCell<File<'a>>> {
-        match self {
-            File::Dir { ref files, .. } | File::Root { ref files, .. } => files,
-            File::Data { .. } => panic!("Called get_files on non-dir file"),
+    /// Directory reached from `cwd` by `cd into`
+    fn cd(&self, cwd: NodeId, into: &str) -> Result<NodeId, FsError> {
+        match into {
+            "/" => Ok(FsTree::ROOT),
+            ".." => self.nodes[cwd].parent.ok_or(FsError::NoParent),
+            name => self
+                .child(cwd, name)
+                .filter(|&id| self.is_dir(id))
+                .ok_or_else(|| FsError::NoSuchDir {
+                    name: name.to_string(),
+                    path: self.path(cwd),
+                }),
         }
     }
 
-    fn get_files_mut(&mut self) -> &mut Vec<RcCell<File<'a>>> {
-        match self {
-            File::Dir { ref mut files, .. }
-            | File::Root { ref mut files, .. } => files,
-            File::Data { .. } => panic!("Called get_files_mut on non-dir file"),
+    /// Adds the entries printed by `ls` in `dir`
+    fn ls<'a>(
+        &mut self,
+        dir: NodeId,
+        output: impl Iterator<Item = &'a str>,
+    ) -> Result<(), FsError> {
+        match &mut self.nodes[dir].kind {
+            Kind::Dir { listed, .. } if *listed => {
+                return Err(FsError::RepeatedLs(self.path(dir)));
+            }
+            Kind::Dir { listed, .. } => *listed = true,
+            Kind::File { .. } => unreachable!("cd only enters directories"),
         }
+
+        for entry in output {
+            let invalid = || FsError::InvalidEntry(entry.to_string());
+            let (info, name) = entry.split_once(' ').ok_or_else(invalid)?;
+            let kind = match info {
+                "dir" => Kind::Dir {
+                    children: Vec::new(),
+                    listed: false,
+                },
+                size => Kind::File {
+                    size: size.parse().map_err(|_| invalid())?,
+                },
+            };
+            self.add(dir, name, kind);
+        }
+
+        Ok(())
     }
 
-    /// Parsing function for files after 'ls' command. 'self' must be currently
-    /// selected dir on which 'ls' is called. The iterator must not include any
-    /// commands only files returned by 'ls'.
-    fn ls(
-        parent: RcCell<File<'a>>,
-        file_lines: &mut Peekable<impl Iterator<Item = &'a str>>,
-    ) {
-        let mut binding = parent.borrow_mut();
-        let files = binding.get_files_mut();
-
-        while let Some(line) = file_lines.next_if(|s| !s.starts_with('$')) {
-            let mut file_info = line.split_ascii_whitespace();
-            let first = file_info.next().unwrap();
-            let name = file_info.next().unwrap();
-            match first {
-                "dir" => files.push(File::new_dir(Rc::clone(&parent), name)),
-                size => files.push(File::new_data(size.parse().unwrap(), name)),
-            }
+    fn add(&mut self, parent: NodeId, name: &str, kind: Kind) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+            size: Cell::new(None),
+        });
+        if let Kind::Dir { children, .. } = &mut self.nodes[parent].kind {
+            children.push(id);
         }
+        self.invalidate_size(parent);
+        id
     }
 
-    /// self must be dir-like (Root or Dir)
-    fn cd(&self, into: &str, root: RcCell<File<'a>>) -> RcCell<File<'a>> {
-        match into {
-            "/" => root,
-            ".." => self.get_parent(),
-            name => self.get_child(name),
+    fn is_dir(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, Kind::Dir { .. })
+    }
+
+    fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.nodes[id].kind {
+            Kind::Dir { children, .. } => children,
+            Kind::File { .. } => &[],
         }
     }
 
-    /// self must be dir-like (Root or Dir)
-    fn get_child(&self, name: &str) -> RcCell<File<'a>> {
-        let files = self.get_files();
-        let file = files
+    fn child(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        self.children(dir)
             .iter()
-            .find(|&file| file.borrow().get_name() == name)
-            .unwrap();
-        Rc::clone(file)
+            .copied()
+            .find(|&id| self.nodes[id].name == name)
     }
 
-    fn init_sizes(&mut self) -> usize {
-        match self {
-            File::Dir {
-                files,
-                ref mut size,
-                ..
-            }
-            | File::Root {
-                files,
-                ref mut size,
-                ..
-            } => {
-                *size = Some(files.iter().fold(0, |total, file| {
-                    total + file.borrow_mut().init_sizes()
-                }));
-                size.unwrap()
-            }
-            File::Data { data, .. } => *data,
+    /// Node at `path`, either absolute (`/a/e`) or relative to `cwd`
+    /// (`../d/j`). Every step but the last one must be a directory.
+    fn lookup(&self, cwd: NodeId, path: &str) -> Result<NodeId, FsError> {
+        let start = if path.starts_with('/') { FsTree::ROOT } else { cwd };
+        let not_found = || FsError::NotFound(path.to_string());
+
+        path.split('/')
+            .filter(|name| !name.is_empty() && *name != ".")
+            .try_fold(start, |current, name| {
+                if !self.is_dir(current) {
+                    return Err(FsError::NotADir(self.path(current)));
+                }
+                match name {
+                    ".." => Ok(self.nodes[current].parent.unwrap_or(current)),
+                    name => self.child(current, name).ok_or_else(not_found),
+                }
+            })
+    }
+
+    /// Creates an empty directory named `name` inside `parent`
+    fn mkdir(&mut self, parent: NodeId, name: &str) -> Result<NodeId, FsError> {
+        if !self.is_dir(parent) {
+            return Err(FsError::NotADir(self.path(parent)));
+        }
+        if let Some(existing) = self.child(parent, name) {
+            return Err(FsError::AlreadyExists(self.path(existing)));
         }
+        let dir = Kind::Dir {
+            children: Vec::new(),
+            listed: false,
+        };
+        Ok(self.add(parent, name, dir))
     }
 
-    fn get_size(&self) -> usize {
-        match self {
-            File::Dir { size, .. } | File::Root { size, .. } => {
-                size.expect("Called get_size() on unitialized file directory")
-            }
-            File::Data { data, .. } => *data,
+    /// Detaches the node (and everything inside it) from the tree
+    fn remove(&mut self, id: NodeId) -> Result<(), FsError> {
+        let parent = self.nodes[id].parent.ok_or(FsError::RemoveRoot)?;
+        if let Kind::Dir { children, .. } = &mut self.nodes[parent].kind {
+            children.retain(|&child| child != id);
         }
+        self.invalidate_size(parent);
+        Ok(())
     }
-}
 
-fn sum_under_max(dir: &File) -> usize {
-    let files = dir.get_files();
-    files.iter().fold(0, |acc, file| {
-        let file = file.borrow();
-        acc + match *file {
-            File::Dir { .. } | File::Root { .. } => {
-                let size = file.get_size();
-                sum_under_max(&file)
-                    + if size < MAX_SIZE {
-                        //println!("Dir '{}' under max size: {}", file.get_name(), size);
-                        size
-                    } else {
-                        0
-                    }
-            }
-            File::Data { .. } => 0,
+    /// Forgets the cached sizes of `id` and the directories containing it
+    fn invalidate_size(&self, id: NodeId) {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            self.nodes[id].size.set(None);
+            current = self.nodes[id].parent;
         }
-    })
-}
+    }
 
-fn parse(input: &str) -> RcCell<File> {
-    let root: RcCell<File> = Rc::new(RefCell::new(File::Root {
-        files: Vec::with_capacity(32),
-        size: None,
-    }));
-
-    let mut current_dir: RcCell<File> = Rc::clone(&root);
-
-    // parsing
-    let mut lines = input.lines().peekable();
-    while let Some(line) = lines.next() {
-        //println!("{}", line);
-        let mut command = line.split_ascii_whitespace();
-        match command.nth(1).unwrap() {
-            "cd" => {
-                let new_dir = current_dir
-                    .borrow()
-                    .cd(command.next().unwrap(), Rc::clone(&root));
-                current_dir = new_dir;
+    /// Absolute path of the node
+    fn path(&self, id: NodeId) -> String {
+        let mut names = Vec::new();
+        let mut current = Some(id);
+        while let Some(id) = current {
+            names.push(self.nodes[id].name.as_str());
+            current = self.nodes[id].parent;
+        }
+        if names.len() == 1 {
+            return "/".to_string();
+        }
+        names.reverse();
+        names.join("/")
+    }
+
+    /// Size of a file, or of everything inside a directory
+    fn size(&self, id: NodeId) -> usize {
+        let node = &self.nodes[id];
+        if let Some(size) = node.size.get() {
+            return size;
+        }
+        let size = match &node.kind {
+            Kind::File { size } => *size,
+            Kind::Dir { children, .. } => {
+                children.iter().map(|&child| self.size(child)).sum()
             }
-            "ls" => File::ls(Rc::clone(&current_dir), lines.by_ref()),
-            _ => panic!("oops"),
         };
+        node.size.set(Some(size));
+        size
     }
 
-    root.borrow_mut().init_sizes();
-    root
+    /// Every directory (the root included) with its size
+    fn dirs(&self) -> impl Iterator<Item = (NodeId, usize)> + '_ {
+        let mut stack = vec![FsTree::ROOT];
+        std::iter::from_fn(move || {
+            let id = stack.pop()?;
+            let children = self.children(id).iter();
+            stack.extend(children.filter(|&&child| self.is_dir(child)));
+            Some((id, self.size(id)))
+        })
+    }
 }
 
+fn parse(input: &str) -> FsTree { FsTree::from_transcript(input).unwrap() }
+
 pub fn task1(input: &str) -> SolutionResult {
-    let root = parse(input);
+    let tree = parse(input);
 
-    let res = SolutionResult::Unsigned(sum_under_max(&root.borrow()));
-    res
-}
+    let res = tree
+        .dirs()
+        .map(|(_, size)| size)
+        .filter(|&size| size <= MAX_SIZE)
+        .sum();
 
-fn get_smallest_over_thresh(dir: &File, thresh: usize) -> usize {
-    let files = dir.get_files();
-
-    files.iter().fold(SPACE_NEEDED, |acc, file| {
-        let file = file.borrow();
-        match *file {
-            File::Dir { .. } | File::Root { .. } => {
-                let size = file.get_size();
-                if size > thresh {
-                    //println!("Dir '{}' over thresh: {}", file.get_name(), size);
-                    let sub_size = get_smallest_over_thresh(&file, thresh);
-                    min(size, sub_size)
-                } else {
-                    acc
-                }
-            }
-            File::Data { .. } => acc,
-        }
-    })
+    SolutionResult::Unsigned(res)
 }
 
 pub fn task2(input: &str) -> SolutionResult {
-    let root = parse(input);
-
-    let thresh = root.borrow().get_size() - (SPACE_AVAILABLE - SPACE_NEEDED);
-    let res = SolutionResult::Unsigned(get_smallest_over_thresh(
-        &root.borrow(),
-        thresh,
-    ));
-    res
+    let tree = parse(input);
+
+    let used = tree.size(FsTree::ROOT);
+    let to_free = used - (SPACE_AVAILABLE - SPACE_NEEDED);
+    let res = tree
+        .dirs()
+        .map(|(_, size)| size)
+        .filter(|&size| size >= to_free)
+        .min()
+        .unwrap();
+
+    SolutionResult::Unsigned(res)
 }
@@ -0,0 +1,226 @@
+//! Interactive shell to explore (and edit) the filesystem rebuilt from a
+//! terminal transcript.
+
+use std::io::{self, BufRead, Write};
+
+use thiserror::Error;
+
+use super::{FsError, FsTree, Kind, NodeId};
+
+const HELP: &str = "\
+cd <dir>               change the current directory
+ls [path]              list a directory, in the transcript's format
+tree [path]            everything under a directory, with sizes
+du -s [path]           total size of a file or directory
+find [path] -size +N   files and directories bigger than N (-N: smaller)
+rm [-r] <path>         remove a file, or a directory with -r
+mkdir <path>           create an empty directory
+help                   show this message
+exit                   leave the shell";
+
+#[derive(Error, Debug)]
+enum ShellError {
+    #[error(transparent)]
+    Fs(#[from] FsError),
+    #[error("usage: {0}")]
+    Usage(&'static str),
+    #[error("unknown command '{0}', try 'help'")]
+    UnknownCommand(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+struct Shell {
+    tree: FsTree,
+    cwd: NodeId,
+}
+
+impl Shell {
+    /// Runs a command line, returns whether the shell should keep going.
+    fn execute(
+        &mut self,
+        line: &str,
+        out: &mut impl Write,
+    ) -> Result<bool, ShellError> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(true);
+        };
+        let args: Vec<&str> = words.collect();
+
+        match (command, args.as_slice()) {
+            ("exit" | "quit", []) => return Ok(false),
+            ("help", []) => writeln!(out, "{HELP}")?,
+            ("cd", [dir]) => self.cd(dir)?,
+            ("cd", _) => return Err(ShellError::Usage("cd <dir>")),
+            ("ls", [] | [_]) => self.ls(self.target(&args)?, out)?,
+            ("tree", [] | [_]) => {
+                self.print_tree(self.target(&args)?, 0, out)?;
+            }
+            ("du", ["-s", path @ ..]) if path.len() <= 1 => {
+                let id = self.target(path)?;
+                let size = self.tree.size(id);
+                writeln!(out, "{size}\t{}", self.tree.path(id))?;
+            }
+            ("du", _) => return Err(ShellError::Usage("du -s [path]")),
+            ("find", [path @ .., "-size", limit]) if path.len() <= 1 => {
+                let root = self.target(path)?;
+                self.find(root, limit, out)?;
+            }
+            ("find", _) => {
+                return Err(ShellError::Usage("find [path] -size +N"));
+            }
+            ("rm", [path]) if !path.starts_with('-') => self.rm(path, false)?,
+            ("rm", ["-r", path]) => self.rm(path, true)?,
+            ("rm", _) => return Err(ShellError::Usage("rm [-r] <path>")),
+            ("mkdir", [path]) => self.mkdir(path)?,
+            ("mkdir", _) => return Err(ShellError::Usage("mkdir <path>")),
+            _ => return Err(ShellError::UnknownCommand(line.to_string())),
+        }
+
+        Ok(true)
+    }
+
+    /// Node at the optional path argument, the current directory if there's
+    /// none
+    fn target(&self, args: &[&str]) -> Result<NodeId, FsError> {
+        match args {
+            [path] => self.tree.lookup(self.cwd, path),
+            _ => Ok(self.cwd),
+        }
+    }
+
+    fn cd(&mut self, dir: &str) -> Result<(), FsError> {
+        // Plain names behave exactly like in the transcript
+        if dir == "/" || !dir.contains('/') {
+            self.cwd = self.tree.cd(self.cwd, dir)?;
+            return Ok(());
+        }
+        let id = self.tree.lookup(self.cwd, dir)?;
+        if !self.tree.is_dir(id) {
+            return Err(FsError::NotADir(self.tree.path(id)));
+        }
+        self.cwd = id;
+        Ok(())
+    }
+
+    fn ls(&self, dir: NodeId, out: &mut impl Write) -> io::Result<()> {
+        for &id in self.tree.children(dir) {
+            let node = &self.tree.nodes[id];
+            match node.kind {
+                Kind::Dir { .. } => writeln!(out, "dir {}", node.name)?,
+                Kind::File { size } => writeln!(out, "{size} {}", node.name)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Same format as the example in the task, with directory sizes too
+    fn print_tree(
+        &self,
+        id: NodeId,
+        depth: usize,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        let node = &self.tree.nodes[id];
+        let name = if node.parent.is_none() { "/" } else { &node.name };
+        let kind = if self.tree.is_dir(id) { "dir" } else { "file" };
+        let indent = "  ".repeat(depth);
+        let size = self.tree.size(id);
+        writeln!(out, "{indent}- {name} ({kind}, size={size})")?;
+
+        for &child in self.tree.children(id) {
+            self.print_tree(child, depth + 1, out)?;
+        }
+        Ok(())
+    }
+
+    /// Every node under `root` (included) whose size is over `+N` or under
+    /// `-N`
+    fn find(
+        &self,
+        root: NodeId,
+        limit: &str,
+        out: &mut impl Write,
+    ) -> Result<(), ShellError> {
+        let usage = || ShellError::Usage("find [path] -size +N");
+        let (bigger, n) = match limit.split_at_checked(1) {
+            Some(("+", n)) => (true, n),
+            Some(("-", n)) => (false, n),
+            _ => return Err(usage()),
+        };
+        let n: usize = n.parse().map_err(|_| usage())?;
+
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            let size = self.tree.size(id);
+            if (bigger && size > n) || (!bigger && size < n) {
+                writeln!(out, "{}", self.tree.path(id))?;
+            }
+            stack.extend(self.tree.children(id).iter().rev());
+        }
+        Ok(())
+    }
+
+    fn rm(&mut self, path: &str, recursive: bool) -> Result<(), ShellError> {
+        let id = self.tree.lookup(self.cwd, path)?;
+        if self.tree.is_dir(id) && !recursive {
+            return Err(ShellError::Usage("rm -r <dir> to remove directories"));
+        }
+        // Don't leave the shell inside a directory that's gone
+        let mut current = Some(self.cwd);
+        while let Some(dir) = current {
+            if dir == id {
+                self.cwd = self.tree.nodes[id].parent.unwrap_or(FsTree::ROOT);
+                break;
+            }
+            current = self.tree.nodes[dir].parent;
+        }
+        Ok(self.tree.remove(id)?)
+    }
+
+    fn mkdir(&mut self, path: &str) -> Result<(), ShellError> {
+        let path = path.trim_end_matches('/');
+        let (parent, name) = match path.rsplit_once('/') {
+            Some(("", name)) => (FsTree::ROOT, name),
+            Some((parent, name)) => (self.tree.lookup(self.cwd, parent)?, name),
+            None => (self.cwd, path),
+        };
+        if name.is_empty() || name == "." || name == ".." {
+            return Err(ShellError::Usage("mkdir <path>"));
+        }
+        self.tree.mkdir(parent, name)?;
+        Ok(())
+    }
+}
+
+/// Rebuilds the filesystem from `transcript` and runs commands read from stdin
+/// until `exit` or the end of the input.
+pub fn run(transcript: &str) {
+    let tree = match FsTree::from_transcript(transcript) {
+        Ok(tree) => tree,
+        Err(err) => {
+            eprintln!("Couldn't load the transcript: {err}");
+            return;
+        }
+    };
+    let mut shell = Shell {
+        tree,
+        cwd: FsTree::ROOT,
+    };
+
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("{}$ ", shell.tree.path(shell.cwd));
+        stdout.flush().unwrap();
+
+        let Some(Ok(line)) = lines.next() else { break };
+        match shell.execute(&line, &mut stdout) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(err) => eprintln!("{err}"),
+        }
+    }
+}
@@ -2,7 +2,7 @@ use std::{fmt, ops::Sub, str::FromStr, string::ParseError};
 
 use itertools::Itertools;
 
-use aoc_lib::tooling::SolutionResult;
+use aoc_lib::{structs::bitgrid::BitGrid, tooling::SolutionResult};
 
 #[derive(Clone, Copy)]
 enum Move {
@@ -158,7 +158,64 @@ impl<const N: usize> Rope<N> {
     }
 }
 
+/// Bottom left and top right corners of the area the head moves through,
+/// since every knot follows the head they all stay inside it too.
+fn head_bounds(moves: &[Move]) -> (Position, Position) {
+    let mut head = Position::new(0, 0);
+    let (mut min, mut max) = (head, head);
+
+    for m in moves {
+        let dist = m.get_distance() as i32;
+        match m {
+            Move::Right(_) => head.x += dist,
+            Move::Left(_) => head.x -= dist,
+            Move::Up(_) => head.y += dist,
+            Move::Down(_) => head.y -= dist,
+        }
+        min = Position::new(min.x.min(head.x), min.y.min(head.y));
+        max = Position::new(max.x.max(head.x), max.y.max(head.y));
+    }
+
+    (min, max)
+}
+
 pub fn solve<const N: usize>(input: &str) -> usize {
+    let moves: Vec<Move> =
+        input.lines().map(|l| l.parse::<Move>().unwrap()).collect();
+
+    let (min, max) = head_bounds(&moves);
+    let (width, height) = ((max - min).x + 1, (max - min).y + 1);
+    let mut visited = BitGrid::new(height as usize, width as usize);
+    let mut visit = |p: Position| {
+        let p = p - min;
+        visited.set(p.x as usize, p.y as usize, true);
+    };
+
+    let mut rope = Rope::<N>::init();
+    visit(rope.tail());
+
+    for m in moves {
+        for next in rope.movement(m) {
+            //println!("\n{m}\n{next}");
+            visit(next.tail());
+            rope = next;
+        }
+    }
+
+    visited.count_ones()
+}
+
+pub fn task1(input: &str) -> SolutionResult {
+    SolutionResult::Unsigned(solve::<2>(input))
+}
+
+pub fn task2(input: &str) -> SolutionResult {
+    SolutionResult::Unsigned(solve::<10>(input))
+}
+
+/// [`solve`] keeping every state of the rope and then deduplicating the tail
+/// positions, instead of marking them on a grid
+fn solve_history<const N: usize>(input: &str) -> usize {
     let lines = input.lines();
     let moves = lines.clone().map(|l| l.parse::<Move>().unwrap());
 
@@ -169,20 +226,17 @@ pub fn solve<const N: usize>(input: &str) -> usize {
 
     for m in moves {
         history.extend(history.last().unwrap().movement(m));
-        //println!("\n{m}\n{}", history.last().unwrap());
     }
 
-    history
-        .iter()
-        //.inspect(|r| println!("Head: {0}, Tail: {1}", r.head, r.tail))
-        .unique_by(|r| r.tail())
-        .count()
+    history.iter().unique_by(|r| r.tail()).count()
 }
 
-pub fn task1(input: &str) -> SolutionResult {
-    SolutionResult::Unsigned(solve::<2>(input))
+/// [`task1`] with [`solve_history`], see `--bench`
+pub fn task1_history(input: &str) -> SolutionResult {
+    SolutionResult::Unsigned(solve_history::<2>(input))
 }
 
-pub fn task2(input: &str) -> SolutionResult {
-    SolutionResult::Unsigned(solve::<10>(input))
+/// [`task2`] with [`solve_history`], see `--bench`
+pub fn task2_history(input: &str) -> SolutionResult {
+    SolutionResult::Unsigned(solve_history::<10>(input))
 }
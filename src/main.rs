@@ -3,8 +3,8 @@
 #![feature(anonymous_lifetime_in_impl_trait)]
 #![feature(associated_type_bounds)]
 
-use clap::Parser;
-use std::fmt::Debug;
+use clap::{Parser, Subcommand};
+use std::{fmt::Debug, fs, path::PathBuf};
 
 use aoc_lib::{benchmark::benchmarks, inputs, solutions, tooling::*};
 
@@ -16,6 +16,11 @@ mod day13;
 mod day14;
 mod day15;
 mod day2;
+mod day21;
+mod day22;
+mod day23;
+mod day24;
+mod day25;
 mod day3;
 mod day4;
 mod day5;
@@ -24,17 +29,76 @@ mod day7;
 mod day8;
 mod day9;
 
-// change max day here
-const MAX_DAY: usize = 15;
-const SOLUTIONS: [[Solution; 2]; MAX_DAY] = solutions!(15); // and here!
-const INPUTS: [[&str; 2]; MAX_DAY] = inputs!(15); // and here!
+const MAX_DAY: usize = 25;
+// add the days with solutions here
+const SOLUTIONS: [Option<[Solution; 2]>; MAX_DAY] =
+    solutions!(MAX_DAY; 1..=15, 21..=25);
+const INPUTS: [Option<[&str; 2]>; MAX_DAY] = inputs!(MAX_DAY; 1..=15, 21..=25);
+// other implementations of the tasks, timed next to the solutions by --bench
+const ALTERNATIVES: &[Alternative] = &[
+    Alternative {
+        day: 9,
+        task: 1,
+        name: "history",
+        solution: day9::task1_history,
+    },
+    Alternative {
+        day: 9,
+        task: 2,
+        name: "history",
+        solution: day9::task2_history,
+    },
+    Alternative {
+        day: 14,
+        task: 1,
+        name: "tiles",
+        solution: day14::tiles::task1,
+    },
+    Alternative {
+        day: 14,
+        task: 2,
+        name: "tiles",
+        solution: day14::tiles::task2,
+    },
+    Alternative {
+        day: 13,
+        task: 1,
+        name: "tree",
+        solution: day13::task1_tree,
+    },
+    Alternative {
+        day: 13,
+        task: 2,
+        name: "tree",
+        solution: day13::task2_tree,
+    },
+];
 
 fn main() {
     let args = Args::parse();
+    set_verbose(args.verbose);
+    if let Some(dir) = args.frames {
+        set_frames_dir(dir);
+    }
+
+    if let Some(Command::Shell { transcript }) = args.command {
+        let text = match transcript {
+            Some(path) => match fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(err) => {
+                    eprintln!("Couldn't read {}: {err}", path.display());
+                    return;
+                }
+            },
+            None => load_input(&INPUTS, 7, args.test).to_string(),
+        };
+        day7::shell::run(&text);
+        return;
+    }
 
     if let Some(passes_opt) = args.bench {
         let passes = passes_opt.unwrap_or(100);
-        benchmarks(&INPUTS, &SOLUTIONS, args.day, passes)
+        benchmarks(&INPUTS, &SOLUTIONS, ALTERNATIVES, args.day, passes)
     } else {
         let day = match args.day {
             Some(day) => day,
@@ -45,16 +109,15 @@ fn main() {
         };
         let data = || load_input(&INPUTS, day, args.test);
         let (res1, res2): (SolutionResult, SolutionResult) = match day {
-            day @ 1..=MAX_DAY => {
-                let day = day - 1;
-                (SOLUTIONS[day][0](data()), SOLUTIONS[day][1](data()))
-            }
-            26.. => {
-                eprintln!("Day {day} out of range (max 25)");
-                return;
-            }
+            day @ 1..=MAX_DAY => match SOLUTIONS[day - 1] {
+                Some([task1, task2]) => (task1(data()), task2(data())),
+                None => {
+                    eprintln!("No solution available for day {day}!");
+                    return;
+                }
+            },
             _ => {
-                eprintln!("No solution available for day {day}!");
+                eprintln!("Day {day} out of range (1 to {MAX_DAY})");
                 return;
             }
         };
@@ -77,4 +140,25 @@ struct Args {
 
     #[clap(long)]
     bench: Option<Option<u32>>,
+
+    /// Print how the solutions got their answers, where they can
+    #[clap(short, long)]
+    verbose: bool,
+
+    /// Save the frames of the simulations that can be animated under DIR
+    #[clap(long, value_name = "DIR")]
+    frames: Option<PathBuf>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Explore the filesystem of a day 7 terminal transcript
+    Shell {
+        /// Transcript to load, the day 7 input (or testinput with -t) if
+        /// not given
+        transcript: Option<PathBuf>,
+    },
 }